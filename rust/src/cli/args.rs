@@ -1,7 +1,11 @@
 //! Command-line argument parsing and validation
 
+use crate::config::Config;
+use crate::core::pipeline::Phase;
 use clap::{Parser, Subcommand};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use tracing::{debug, warn};
 
 /// Arch Package Builder - A reliable tool for building Arch Linux packages
 #[derive(Parser, Debug)]
@@ -12,11 +16,33 @@ pub struct Args {
     #[arg(long, global = true)]
     pub debug: bool,
 
+    /// Print the commands that would be executed as a JSON build plan instead of running them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Kill the build or dependency-install command if it runs longer than this many seconds
+    #[arg(long, global = true)]
+    pub timeout: Option<u64>,
+
+    /// Output format: human-readable logs only, or newline-delimited JSON
+    /// lifecycle events alongside them for CI/dashboard consumption
+    #[arg(long, global = true, value_enum, default_value = "human")]
+    pub message_format: MessageFormat,
+
     /// Subcommand to execute
     #[command(subcommand)]
     pub command: Command,
 }
 
+/// Output format for build progress, analogous to cargo's `--message-format`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum MessageFormat {
+    /// Human-readable `tracing` output (default)
+    Human,
+    /// Newline-delimited JSON lifecycle events
+    Json,
+}
+
 /// Available commands
 #[derive(Subcommand, Debug)]
 pub enum Command {
@@ -47,17 +73,130 @@ pub enum Command {
         #[arg(short = 'o', long = "output-file", default_value = "version.env")]
         output_file: PathBuf,
     },
+
+    /// Run a contiguous range of build phases (deps, build, artifacts,
+    /// version) against a single parsed PKGBUILD, instead of re-parsing it
+    /// for each separate subcommand invocation
+    Pipeline {
+        /// First phase to run
+        #[arg(long, value_enum, default_value = "deps")]
+        from: Phase,
+
+        /// Last phase to run
+        #[arg(long, value_enum, default_value = "version")]
+        to: Phase,
+
+        /// Clean previous build artifacts before building
+        #[arg(long)]
+        clean: bool,
+
+        /// Sign the package using GPG
+        #[arg(long)]
+        sign: bool,
+    },
 }
 
-/// Parse command line arguments
+/// Parse command line arguments, expanding a configured CLI alias first if
+/// the raw argv doesn't already match a known subcommand. Mirrors cargo's
+/// alias expansion: `[alias]` entries in `pkgmanager.toml`/the user config
+/// file (e.g. `ci = "build --clean --sign"`) are substituted in as literal
+/// argv before clap ever sees them, so a built-in subcommand always wins
+/// over an alias of the same name.
 pub fn parse_args() -> Args {
-    Args::parse()
+    let raw: Vec<String> = std::env::args().collect();
+
+    match Args::try_parse_from(&raw) {
+        Ok(args) => args,
+        Err(err) => {
+            let aliases = Config::load_aliases();
+            match expand_alias(&raw, &aliases) {
+                Some(expanded) => {
+                    debug!("Expanded CLI alias into: {:?}", expanded);
+                    Args::parse_from(expanded)
+                }
+                None => err.exit(),
+            }
+        }
+    }
+}
+
+/// Substitute the first non-flag token in `raw` for its alias expansion,
+/// repeating until the token no longer names an alias. Returns `None` if the
+/// first non-flag token isn't an alias at all (so the caller can fall back
+/// to clap's normal "unrecognized subcommand" error). Guards against alias
+/// cycles with a visited set, stopping expansion (rather than looping
+/// forever) if an alias ends up referring to itself, directly or
+/// transitively.
+fn expand_alias(raw: &[String], aliases: &HashMap<String, String>) -> Option<Vec<String>> {
+    let mut argv = raw.to_vec();
+    let mut visited: HashSet<String> = HashSet::new();
+    let mut expanded_once = false;
+
+    loop {
+        let idx = argv.iter().enumerate().skip(1).find(|(_, a)| !a.starts_with('-')).map(|(i, _)| i)?;
+        let token = argv[idx].clone();
+
+        let Some(expansion) = aliases.get(&token) else {
+            return expanded_once.then_some(argv);
+        };
+
+        if !visited.insert(token.clone()) {
+            warn!("Alias cycle detected at '{token}', stopping expansion");
+            return Some(argv);
+        }
+
+        let mut next = argv[..idx].to_vec();
+        next.extend(expansion.split_whitespace().map(String::from));
+        next.extend_from_slice(&argv[idx + 1..]);
+        argv = next;
+        expanded_once = true;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
+    fn strs(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_expand_alias_replaces_token() {
+        let aliases = HashMap::from([("ci".to_string(), "build --clean --sign".to_string())]);
+        let expanded = expand_alias(&strs(&["builder", "ci"]), &aliases).unwrap();
+        assert_eq!(expanded, strs(&["builder", "build", "--clean", "--sign"]));
+    }
+
+    #[test]
+    fn test_expand_alias_preserves_leading_flags_and_trailing_args() {
+        let aliases = HashMap::from([("ci".to_string(), "build --clean".to_string())]);
+        let expanded =
+            expand_alias(&strs(&["builder", "--debug", "ci", "--sign"]), &aliases).unwrap();
+        assert_eq!(
+            expanded,
+            strs(&["builder", "--debug", "build", "--clean", "--sign"])
+        );
+    }
+
+    #[test]
+    fn test_expand_alias_returns_none_for_unknown_token() {
+        let aliases = HashMap::from([("ci".to_string(), "build --clean".to_string())]);
+        assert!(expand_alias(&strs(&["builder", "deps"]), &aliases).is_none());
+    }
+
+    #[test]
+    fn test_expand_alias_detects_cycle() {
+        let aliases = HashMap::from([
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "a".to_string()),
+        ]);
+        // Should terminate instead of looping forever, returning whatever it
+        // last expanded to.
+        let expanded = expand_alias(&strs(&["builder", "a"]), &aliases).unwrap();
+        assert!(expanded.contains(&"a".to_string()) || expanded.contains(&"b".to_string()));
+    }
+
     #[test]
     fn test_parse_basic_args() {
         let args = Args::try_parse_from(["builder", "deps"]).unwrap();
@@ -82,4 +221,32 @@ mod tests {
             _ => panic!("Expected Build command"),
         }
     }
+
+    #[test]
+    fn test_parse_pipeline_with_range() {
+        let args =
+            Args::try_parse_from(["builder", "pipeline", "--from", "deps", "--to", "artifacts"])
+                .unwrap();
+        match args.command {
+            Command::Pipeline { from, to, clean, sign } => {
+                assert_eq!(from, Phase::Deps);
+                assert_eq!(to, Phase::Artifacts);
+                assert!(!clean);
+                assert!(!sign);
+            }
+            _ => panic!("Expected Pipeline command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_pipeline_defaults_to_full_range() {
+        let args = Args::try_parse_from(["builder", "pipeline"]).unwrap();
+        match args.command {
+            Command::Pipeline { from, to, .. } => {
+                assert_eq!(from, Phase::Deps);
+                assert_eq!(to, Phase::Version);
+            }
+            _ => panic!("Expected Pipeline command"),
+        }
+    }
 }
\ No newline at end of file
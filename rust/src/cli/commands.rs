@@ -3,8 +3,17 @@
 use crate::{
     cli::Command,
     config::Config,
-    core::{artifacts::ArtifactCollector, builder::PackageBuilder, pkgbuild::PkgbuildParser},
-    utils::env::VersionGenerator,
+    core::{
+        artifacts::ArtifactCollector,
+        builder::PackageBuilder,
+        makepkg::MakePkgOptions,
+        pipeline::{self, Phase, PhaseRange},
+        pkgbuild::PkgbuildParser,
+    },
+    utils::{
+        env::VersionGenerator,
+        events::{self, BuildEvent},
+    },
 };
 use anyhow::Context;
 use tracing::{info, instrument};
@@ -12,12 +21,21 @@ use tracing::{info, instrument};
 /// Execute the appropriate command based on CLI arguments
 #[instrument(skip(config))]
 pub fn execute_command(config: &Config, command: &Command) -> anyhow::Result<()> {
-    match command {
+    let result = match command {
         Command::Deps => execute_deps_command(config),
         Command::Build { .. } => execute_build_command(config),
         Command::Artifacts { .. } => execute_artifacts_command(config),
         Command::Version { .. } => execute_version_command(config),
+        Command::Pipeline { from, to, .. } => execute_pipeline_command(config, *from, *to),
+    };
+
+    if let Err(e) = &result {
+        events::sink_for(config.json_events).emit(&BuildEvent::BuildError {
+            message: e.to_string(),
+        });
     }
+
+    result
 }
 
 /// Execute the dependencies command
@@ -35,7 +53,11 @@ fn execute_deps_command(config: &Config) -> anyhow::Result<()> {
         .install_dependencies(&pkgbuild)
         .context("Failed to install dependencies")?;
 
-    info!("Dependencies installation completed successfully");
+    if config.build.dry_run {
+        print_build_plan(&builder)?;
+    } else {
+        info!("Dependencies installation completed successfully");
+    }
     Ok(())
 }
 
@@ -56,15 +78,27 @@ fn execute_build_command(config: &Config) -> anyhow::Result<()> {
     }
 
     let package_files = builder
-        .build(&pkgbuild)
+        .build(&pkgbuild, &MakePkgOptions::default())
         .context("Failed to build package")?;
 
-    info!(
-        "Build completed successfully. Generated {} package(s): {:?}",
-        package_files.len(),
-        package_files
-    );
+    if config.build.dry_run {
+        print_build_plan(&builder)?;
+    } else {
+        info!(
+            "Build completed successfully. Generated {} package(s): {:?}",
+            package_files.len(),
+            package_files
+        );
+    }
+
+    Ok(())
+}
 
+/// Print the commands a dry-run builder would have executed as a JSON build plan
+fn print_build_plan(builder: &PackageBuilder) -> anyhow::Result<()> {
+    let plan = builder.planned_invocations();
+    let json = serde_json::to_string_pretty(&plan).context("Failed to serialize build plan")?;
+    println!("{json}");
     Ok(())
 }
 
@@ -87,6 +121,25 @@ fn execute_artifacts_command(config: &Config) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Execute a contiguous range of build phases against a single parsed
+/// PKGBUILD, short-circuiting before `from` and stopping after `to`
+#[instrument(skip(config))]
+fn execute_pipeline_command(config: &Config, from: Phase, to: Phase) -> anyhow::Result<()> {
+    let range = PhaseRange::new(from, to).context("Invalid pipeline phase range")?;
+
+    info!("Running pipeline phases {:?}..={:?}", range.from, range.to);
+    let report = pipeline::run(config, range).context("Pipeline failed")?;
+
+    if !report.package_files.is_empty() {
+        info!("Built {} package(s): {:?}", report.package_files.len(), report.package_files);
+    }
+    if !report.collected_artifacts.is_empty() {
+        info!("Collected {} artifact(s)", report.collected_artifacts.len());
+    }
+
+    Ok(())
+}
+
 /// Execute the version command
 #[instrument(skip(config))]
 fn execute_version_command(config: &Config) -> anyhow::Result<()> {
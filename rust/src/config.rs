@@ -2,9 +2,11 @@
 //!
 //! Centralizes configuration options and provides validation.
 
-use crate::{cli::Args, error::BuilderError};
+use crate::{cli::Args, error::BuilderError, utils::strings::closest_match};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +23,15 @@ pub struct Config {
     pub build: BuildConfig,
     /// Artifact configuration
     pub artifacts: ArtifactConfig,
+    /// Emit newline-delimited JSON lifecycle events to stdout in addition to
+    /// the human-readable `tracing` logs (`--message-format=json`), for
+    /// CI/dashboard consumption.
+    pub json_events: bool,
+    /// User-defined CLI aliases (the `[alias]` table), e.g. `ci = "build
+    /// --clean --sign"`. Expanded by [`crate::cli::parse_args`] before clap
+    /// ever sees the argv; kept here too so the rest of the config layering
+    /// (user file, then project file) applies to it the same way.
+    pub aliases: HashMap<String, String>,
 }
 
 /// Package manager configuration
@@ -34,6 +45,13 @@ pub struct PackageManagerConfig {
     pub install_args: Vec<String>,
     /// Handle rust/rustup conflicts
     pub handle_rust_conflict: bool,
+    /// Privilege-escalation tool for commands that require root (`sudo`, `run0`,
+    /// `pkexec`, `doas`). `None` auto-detects the first one available in PATH.
+    pub escalation: Option<String>,
+    /// Recursively resolve AUR dependencies via the AUR RPC before
+    /// installing, instead of handing the flat dependency list straight to
+    /// paru/pacman and trusting it to find them.
+    pub resolve_aur_deps: bool,
 }
 
 /// Build configuration
@@ -49,6 +67,12 @@ pub struct BuildConfig {
     pub ccache_dir: PathBuf,
     /// Additional build arguments
     pub build_args: Vec<String>,
+    /// Record commands that would run instead of executing them
+    pub dry_run: bool,
+    /// Kill the build or dependency-install command if it runs longer than
+    /// this. `None` waits
+    /// indefinitely.
+    pub timeout: Option<std::time::Duration>,
 }
 
 /// Artifact configuration
@@ -62,6 +86,217 @@ pub struct ArtifactConfig {
     pub patterns: Vec<String>,
     /// Whether to preserve source files
     pub preserve_sources: bool,
+    /// Verify each collected package's detached GPG signature (`<pkg>.sig`),
+    /// failing collection if one exists but doesn't check out
+    pub verify_signatures: bool,
+    /// Keyring to pass to `gpg --verify` via `--keyring`. `None` uses gpg's
+    /// default keyring.
+    pub gpg_keyring: Option<PathBuf>,
+    /// Write a `SHA256SUMS` manifest alongside the collected packages
+    pub write_checksums: bool,
+}
+
+/// Name of the project-level config file, looked for in `work_dir`.
+const PROJECT_CONFIG_FILE: &str = "pkgmanager.toml";
+
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "debug",
+    "work_dir",
+    "pkgbuild_path",
+    "package_manager",
+    "build",
+    "artifacts",
+    "json_events",
+    "alias",
+];
+const PACKAGE_MANAGER_KEYS: &[&str] = &[
+    "primary",
+    "fallback",
+    "install_args",
+    "handle_rust_conflict",
+    "escalation",
+    "resolve_aur_deps",
+];
+const BUILD_KEYS: &[&str] = &[
+    "clean",
+    "sign",
+    "use_ccache",
+    "ccache_dir",
+    "build_args",
+    "dry_run",
+    "timeout_secs",
+];
+const ARTIFACT_KEYS: &[&str] = &[
+    "output_dir",
+    "version_file",
+    "patterns",
+    "preserve_sources",
+    "verify_signatures",
+    "gpg_keyring",
+    "write_checksums",
+];
+
+/// Maximum edit distance for a typo'd config key to be suggested as a fix.
+const SUGGESTION_MAX_DISTANCE: usize = 3;
+
+/// A config file as read from TOML, with every field optional so a file only
+/// needs to specify what it wants to override. Merged onto [`Config`] with
+/// [`apply_file`] in layered, cargo-like precedence.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ConfigFile {
+    debug: Option<bool>,
+    work_dir: Option<PathBuf>,
+    pkgbuild_path: Option<PathBuf>,
+    package_manager: Option<PackageManagerFile>,
+    build: Option<BuildFile>,
+    artifacts: Option<ArtifactFile>,
+    json_events: Option<bool>,
+    alias: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct PackageManagerFile {
+    primary: Option<String>,
+    fallback: Option<String>,
+    install_args: Option<Vec<String>>,
+    handle_rust_conflict: Option<bool>,
+    escalation: Option<String>,
+    resolve_aur_deps: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct BuildFile {
+    clean: Option<bool>,
+    sign: Option<bool>,
+    use_ccache: Option<bool>,
+    ccache_dir: Option<PathBuf>,
+    build_args: Option<Vec<String>>,
+    dry_run: Option<bool>,
+    /// Build timeout in seconds; TOML has no native duration type.
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ArtifactFile {
+    output_dir: Option<PathBuf>,
+    version_file: Option<PathBuf>,
+    patterns: Option<Vec<String>>,
+    preserve_sources: Option<bool>,
+    verify_signatures: Option<bool>,
+    gpg_keyring: Option<PathBuf>,
+    write_checksums: Option<bool>,
+}
+
+/// Apply every field a config file set, in order, onto `config`. Fields left
+/// `None` are untouched, so earlier (lower-precedence) layers show through.
+fn apply_file(config: &mut Config, file: ConfigFile) {
+    if let Some(debug) = file.debug {
+        config.debug = debug;
+    }
+    if let Some(work_dir) = file.work_dir {
+        config.work_dir = work_dir;
+    }
+    if let Some(path) = file.pkgbuild_path {
+        config.pkgbuild_path = path;
+    }
+    if let Some(json_events) = file.json_events {
+        config.json_events = json_events;
+    }
+    if let Some(alias) = file.alias {
+        config.aliases.extend(alias);
+    }
+
+    if let Some(pm) = file.package_manager {
+        if let Some(v) = pm.primary {
+            config.package_manager.primary = v;
+        }
+        if let Some(v) = pm.fallback {
+            config.package_manager.fallback = Some(v);
+        }
+        if let Some(v) = pm.install_args {
+            config.package_manager.install_args = v;
+        }
+        if let Some(v) = pm.handle_rust_conflict {
+            config.package_manager.handle_rust_conflict = v;
+        }
+        if let Some(v) = pm.escalation {
+            config.package_manager.escalation = Some(v);
+        }
+        if let Some(v) = pm.resolve_aur_deps {
+            config.package_manager.resolve_aur_deps = v;
+        }
+    }
+
+    if let Some(build) = file.build {
+        if let Some(v) = build.clean {
+            config.build.clean = v;
+        }
+        if let Some(v) = build.sign {
+            config.build.sign = v;
+        }
+        if let Some(v) = build.use_ccache {
+            config.build.use_ccache = v;
+        }
+        if let Some(v) = build.ccache_dir {
+            config.build.ccache_dir = v;
+        }
+        if let Some(v) = build.build_args {
+            config.build.build_args = v;
+        }
+        if let Some(v) = build.dry_run {
+            config.build.dry_run = v;
+        }
+        if let Some(secs) = build.timeout_secs {
+            config.build.timeout = Some(Duration::from_secs(secs));
+        }
+    }
+
+    if let Some(artifacts) = file.artifacts {
+        if let Some(v) = artifacts.output_dir {
+            config.artifacts.output_dir = v;
+        }
+        if let Some(v) = artifacts.version_file {
+            config.artifacts.version_file = v;
+        }
+        if let Some(v) = artifacts.patterns {
+            config.artifacts.patterns = v;
+        }
+        if let Some(v) = artifacts.preserve_sources {
+            config.artifacts.preserve_sources = v;
+        }
+        if let Some(v) = artifacts.verify_signatures {
+            config.artifacts.verify_signatures = v;
+        }
+        if let Some(v) = artifacts.gpg_keyring {
+            config.artifacts.gpg_keyring = Some(v);
+        }
+        if let Some(v) = artifacts.write_checksums {
+            config.artifacts.write_checksums = v;
+        }
+    }
+}
+
+/// Reject unknown keys in a TOML table, suggesting the closest known key by
+/// edit distance when one is close enough to likely be a typo.
+fn check_unknown_keys(
+    table: &toml::value::Table,
+    known: &[&str],
+    prefix: &str,
+) -> Result<(), BuilderError> {
+    for key in table.keys() {
+        if known.iter().any(|k| k == key) {
+            continue;
+        }
+
+        let message = closest_match(key, known, SUGGESTION_MAX_DISTANCE).map_or_else(
+            || format!("Unknown configuration key `{prefix}{key}`"),
+            |suggestion| {
+                format!("Unknown configuration key `{prefix}{key}`, did you mean `{prefix}{suggestion}`?")
+            },
+        );
+        return Err(BuilderError::validation(message));
+    }
+    Ok(())
 }
 
 impl Default for Config {
@@ -73,6 +308,8 @@ impl Default for Config {
             package_manager: PackageManagerConfig::default(),
             build: BuildConfig::default(),
             artifacts: ArtifactConfig::default(),
+            json_events: false,
+            aliases: HashMap::new(),
         }
     }
 }
@@ -89,6 +326,8 @@ impl Default for PackageManagerConfig {
                 "--asdeps".to_string(),
             ],
             handle_rust_conflict: true,
+            escalation: None,
+            resolve_aur_deps: false,
         }
     }
 }
@@ -101,6 +340,8 @@ impl Default for BuildConfig {
             use_ccache: true,
             ccache_dir: PathBuf::from("/home/builder/.ccache"),
             build_args: vec!["-B".to_string(), "--noconfirm".to_string()],
+            dry_run: false,
+            timeout: None,
         }
     }
 }
@@ -117,23 +358,55 @@ impl Default for ArtifactConfig {
                 ".SRCINFO".to_string(),
             ],
             preserve_sources: true,
+            verify_signatures: false,
+            gpg_keyring: None,
+            write_checksums: false,
         }
     }
 }
 
 impl Config {
-    /// Create configuration from command line arguments
+    /// Create configuration from command line arguments, layering in config
+    /// files along the way: CLI flags take precedence over the project file
+    /// (`pkgmanager.toml` in `work_dir`), which takes precedence over the
+    /// user file (`~/.config/pkgmanager/config.toml`), which takes precedence
+    /// over built-in defaults.
     pub fn from_args(args: &Args) -> Result<Self, BuilderError> {
-        let mut config = Self {
-            debug: args.debug,
-            ..Self::default()
-        };
-        
+        let mut config = Self::default();
+
+        if let Some(user_path) = Self::user_config_path() {
+            if let Some(file) = Self::load_config_file(&user_path)? {
+                apply_file(&mut config, file);
+            }
+        }
+
+        let project_path = config.work_dir.join(PROJECT_CONFIG_FILE);
+        if let Some(file) = Self::load_config_file(&project_path)? {
+            apply_file(&mut config, file);
+        }
+
+        if args.debug {
+            config.debug = true;
+        }
+        if args.dry_run {
+            config.build.dry_run = true;
+        }
+        if let Some(timeout_secs) = args.timeout {
+            config.build.timeout = Some(Duration::from_secs(timeout_secs));
+        }
+        if args.message_format == crate::cli::MessageFormat::Json {
+            config.json_events = true;
+        }
+
         // Override with command-specific options
         match &args.command {
             crate::cli::Command::Build { clean, sign } => {
-                config.build.clean = *clean;
-                config.build.sign = *sign;
+                if *clean {
+                    config.build.clean = true;
+                }
+                if *sign {
+                    config.build.sign = true;
+                }
             }
             crate::cli::Command::Artifacts { output_dir } => {
                 config.artifacts.output_dir = output_dir.clone();
@@ -141,12 +414,93 @@ impl Config {
             crate::cli::Command::Version { output_file } => {
                 config.artifacts.version_file = output_file.clone();
             }
+            crate::cli::Command::Pipeline { clean, sign, .. } => {
+                if *clean {
+                    config.build.clean = true;
+                }
+                if *sign {
+                    config.build.sign = true;
+                }
+            }
             _ => {}
         }
-        
+
         config.validate()?;
         Ok(config)
     }
+
+    /// Load just the `[alias]` table from the user and project config files,
+    /// layered the same way [`Self::from_args`] layers everything else
+    /// (project overrides user). Standalone because CLI alias expansion has
+    /// to happen *before* a full [`Args`]/[`Config`] exists: the argv is what
+    /// produces `Args` in the first place. Read failures (missing file,
+    /// invalid TOML) are treated as "no aliases" rather than a hard error, so
+    /// a broken config file doesn't block built-in subcommands from working.
+    #[must_use]
+    pub fn load_aliases() -> HashMap<String, String> {
+        let mut aliases = HashMap::new();
+
+        if let Some(user_path) = Self::user_config_path() {
+            if let Ok(Some(file)) = Self::load_config_file(&user_path) {
+                if let Some(alias) = file.alias {
+                    aliases.extend(alias);
+                }
+            }
+        }
+
+        let work_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let project_path = work_dir.join(PROJECT_CONFIG_FILE);
+        if let Ok(Some(file)) = Self::load_config_file(&project_path) {
+            if let Some(alias) = file.alias {
+                aliases.extend(alias);
+            }
+        }
+
+        aliases
+    }
+
+    /// Path to the user-level config file (`~/.config/pkgmanager/config.toml`),
+    /// or `None` if `$HOME` isn't set.
+    fn user_config_path() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .map(|home| PathBuf::from(home).join(".config/pkgmanager").join("config.toml"))
+    }
+
+    /// Load and validate a TOML config file, returning `None` if it doesn't
+    /// exist. Unknown keys are rejected with a "did you mean" suggestion.
+    fn load_config_file(path: &Path) -> Result<Option<ConfigFile>, BuilderError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| BuilderError::file_system("read config file", path, e))?;
+
+        let value: toml::Value = content
+            .parse()
+            .map_err(|e| BuilderError::config(format!("Failed to parse {}: {e}", path.display())))?;
+
+        let table = value
+            .as_table()
+            .ok_or_else(|| BuilderError::config(format!("{} must be a TOML table", path.display())))?;
+
+        check_unknown_keys(table, TOP_LEVEL_KEYS, "")?;
+        if let Some(pm) = table.get("package_manager").and_then(toml::Value::as_table) {
+            check_unknown_keys(pm, PACKAGE_MANAGER_KEYS, "package_manager.")?;
+        }
+        if let Some(build) = table.get("build").and_then(toml::Value::as_table) {
+            check_unknown_keys(build, BUILD_KEYS, "build.")?;
+        }
+        if let Some(artifacts) = table.get("artifacts").and_then(toml::Value::as_table) {
+            check_unknown_keys(artifacts, ARTIFACT_KEYS, "artifacts.")?;
+        }
+
+        let parsed: ConfigFile = toml::from_str(&content).map_err(|e| {
+            BuilderError::config(format!("Invalid configuration in {}: {e}", path.display()))
+        })?;
+
+        Ok(Some(parsed))
+    }
     
     /// Validate configuration
     pub fn validate(&self) -> Result<(), BuilderError> {
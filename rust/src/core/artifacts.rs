@@ -5,15 +5,24 @@
 use crate::{
     config::Config,
     error::{BuilderError, Result},
-    utils::fs::FileSystemUtils,
+    utils::{
+        events::{self, BuildEvent, EventSink},
+        fs::FileSystemUtils,
+        process::ProcessRunner,
+        transaction::Transaction,
+    },
 };
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{debug, info, instrument, warn};
 
 /// Artifact collector that gathers build outputs
 pub struct ArtifactCollector {
     config: Config,
     fs_utils: FileSystemUtils,
+    process_runner: ProcessRunner,
+    event_sink: Arc<dyn EventSink>,
 }
 
 /// Information about collected artifacts
@@ -39,8 +48,11 @@ pub enum ArtifactOperation {
 impl ArtifactCollector {
     /// Create a new artifact collector
     pub fn new(config: Config) -> Self {
+        let event_sink = events::sink_for(config.json_events);
         Self {
             fs_utils: FileSystemUtils::new(),
+            process_runner: ProcessRunner::new(config.debug),
+            event_sink,
             config,
         }
     }
@@ -65,10 +77,14 @@ impl ArtifactCollector {
 
         let mut collected = Vec::new();
         let mut found_packages = false;
+        // Rolls back every destination this call writes if we bail out
+        // before committing below, so a failed collection never leaves a
+        // half-populated output directory behind.
+        let mut tx = Transaction::new();
 
         // Collect files for each pattern
         for pattern in &self.config.artifacts.patterns {
-            let artifacts = self.collect_pattern(pattern)?;
+            let artifacts = self.collect_pattern(pattern, &mut tx)?;
 
             // Check if we found any package files
             if pattern.contains(".pkg.tar.") && !artifacts.is_empty() {
@@ -86,13 +102,136 @@ impl ArtifactCollector {
             ));
         }
 
+        self.verify_artifacts(&collected, &mut tx)?;
+
         info!("Successfully collected {} artifacts", collected.len());
+        tx.commit();
         Ok(collected)
     }
 
+    /// Verify each collected package's detached GPG signature (if
+    /// `verify_signatures` is set) and/or write a `SHA256SUMS` manifest
+    /// alongside them (if `write_checksums` is set), so downstream
+    /// repo-publishing steps can trust the collected output.
+    #[instrument(skip(self, collected, tx))]
+    fn verify_artifacts(&self, collected: &[CollectedArtifact], tx: &mut Transaction) -> Result<()> {
+        if !self.config.artifacts.verify_signatures && !self.config.artifacts.write_checksums {
+            return Ok(());
+        }
+
+        let mut checksum_lines = Vec::new();
+
+        for artifact in collected {
+            let is_package = artifact
+                .destination
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|name| name.contains(".pkg.tar."));
+
+            if !is_package {
+                continue;
+            }
+
+            if self.config.artifacts.verify_signatures {
+                self.verify_signature(artifact, tx)?;
+            }
+
+            if self.config.artifacts.write_checksums {
+                checksum_lines.push(Self::checksum_line(&artifact.destination)?);
+            }
+        }
+
+        if self.config.artifacts.write_checksums && !checksum_lines.is_empty() {
+            let manifest_path = self.config.artifacts.output_dir.join("SHA256SUMS");
+            self.fs_utils
+                .write_file(&manifest_path, format!("{}\n", checksum_lines.join("\n")))
+                .map_err(|e| {
+                    BuilderError::artifact(
+                        format!("Failed to write checksum manifest: {e}"),
+                        &manifest_path,
+                    )
+                })?;
+            tx.track_created(manifest_path.clone());
+            info!("Wrote checksum manifest: {}", manifest_path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Verify `artifact`'s detached signature against the configured
+    /// keyring, if a sibling `.sig` file exists next to the original
+    /// (pre-collection) file. Does nothing if no signature is present.
+    fn verify_signature(&self, artifact: &CollectedArtifact, tx: &mut Transaction) -> Result<()> {
+        let sig_source = PathBuf::from(format!("{}.sig", artifact.source.display()));
+        if !sig_source.exists() {
+            debug!(
+                "No signature found for {}, skipping verification",
+                artifact.source.display()
+            );
+            return Ok(());
+        }
+
+        let sig_destination = PathBuf::from(format!("{}.sig", artifact.destination.display()));
+        self.fs_utils
+            .copy_file(&sig_source, &sig_destination)
+            .map_err(|e| {
+                BuilderError::artifact(format!("Failed to collect signature: {e}"), &sig_source)
+            })?;
+        tx.track_created(sig_destination.clone());
+
+        let mut command = self.process_runner.command("gpg").arg("--verify");
+        if let Some(keyring) = &self.config.artifacts.gpg_keyring {
+            command = command.arg("--keyring").arg(keyring.to_string_lossy().to_string());
+        }
+        let result = command
+            .arg(sig_destination.to_string_lossy().to_string())
+            .arg(artifact.destination.to_string_lossy().to_string())
+            .output()
+            .map_err(|e| {
+                BuilderError::artifact(
+                    format!("Failed to run gpg for {}: {e}", artifact.destination.display()),
+                    &artifact.destination,
+                )
+            })?;
+
+        if !result.success {
+            return Err(BuilderError::artifact(
+                format!(
+                    "Signature verification failed for {}: {}",
+                    artifact.destination.display(),
+                    result.stderr.trim()
+                ),
+                &artifact.destination,
+            ));
+        }
+
+        debug!("Signature verified for {}", artifact.destination.display());
+        Ok(())
+    }
+
+    /// Compute a `sha256sum`-formatted line (`<hex digest>  <file name>`) for `path`
+    fn checksum_line(path: &Path) -> Result<String> {
+        let bytes = std::fs::read(path).map_err(|e| {
+            BuilderError::artifact(
+                format!("Failed to read {} for checksum: {e}", path.display()),
+                path.to_path_buf(),
+            )
+        })?;
+
+        let digest = Sha256::digest(&bytes);
+        let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+        Ok(format!("{hex}  {file_name}"))
+    }
+
     /// Collect files matching a specific pattern
-    #[instrument(skip(self))]
-    fn collect_pattern(&self, pattern: &str) -> Result<Vec<CollectedArtifact>> {
+    #[instrument(skip(self, tx))]
+    fn collect_pattern(
+        &self,
+        pattern: &str,
+        tx: &mut Transaction,
+    ) -> Result<Vec<CollectedArtifact>> {
         debug!("Collecting files matching pattern: {}", pattern);
         let mut artifacts = Vec::new();
 
@@ -106,7 +245,7 @@ impl ArtifactCollector {
         };
 
         for file_path in files {
-            let artifact = self.collect_file(&file_path, pattern)?;
+            let artifact = self.collect_file(&file_path, pattern, tx)?;
             artifacts.push(artifact);
         }
 
@@ -176,9 +315,15 @@ impl ArtifactCollector {
         Ok(files)
     }
 
-    /// Collect a single file
-    #[instrument(skip(self))]
-    fn collect_file(&self, file_path: &Path, pattern: &str) -> Result<CollectedArtifact> {
+    /// Collect a single file, registering its destination with `tx` so a
+    /// subsequent failure rolls it back
+    #[instrument(skip(self, tx))]
+    fn collect_file(
+        &self,
+        file_path: &Path,
+        pattern: &str,
+        tx: &mut Transaction,
+    ) -> Result<CollectedArtifact> {
         let file_name = file_path
             .file_name()
             .and_then(|n| n.to_str())
@@ -209,6 +354,7 @@ impl ArtifactCollector {
                     file_path.display(),
                     destination.display()
                 );
+                tx.track_created(destination.clone());
             }
             ArtifactOperation::Moved => {
                 self.fs_utils
@@ -224,9 +370,15 @@ impl ArtifactCollector {
                     file_path.display(),
                     destination.display()
                 );
+                tx.track_moved(file_path.to_path_buf(), destination.clone());
             }
         }
 
+        self.event_sink.emit(&BuildEvent::ArtifactCollected {
+            source: file_path.display().to_string(),
+            destination: destination.display().to_string(),
+        });
+
         Ok(CollectedArtifact {
             source: file_path.to_path_buf(),
             destination,
@@ -408,4 +560,19 @@ mod tests {
         assert_eq!(summary.copied, 1);
         assert_eq!(summary.moved, 2);
     }
+
+    #[test]
+    fn test_checksum_line_format() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test-1.0.0-1-x86_64.pkg.tar.zst");
+        fs::write(&file_path, "test content").unwrap();
+
+        let line = ArtifactCollector::checksum_line(&file_path).unwrap();
+
+        // sha256("test content")
+        assert_eq!(
+            line,
+            "6ae8a75555209fd6c44157c0aed8016e763ff435a19cf186f76863140143ff72  test-1.0.0-1-x86_64.pkg.tar.zst"
+        );
+    }
 }
@@ -0,0 +1,238 @@
+//! Recursive AUR dependency resolution
+//!
+//! `install_dependencies` normally hands its flat dependency list straight
+//! to paru/pacman and trusts it to sort out which ones live in the AUR.
+//! [`AurResolver`] instead walks the AUR RPC `info` endpoint to transitively
+//! expand `Depends`/`MakeDepends` for anything not present in the official
+//! repos, and returns a topologically ordered [`DependencyPlan`] so AUR
+//! packages are built in an order where every dependency comes before its
+//! dependents.
+
+use crate::error::{BuilderError, Result};
+use crate::utils::process::ProcessRunner;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, instrument, warn};
+
+/// AUR RPC v5 `info` endpoint; a single `type=info` call already returns the
+/// full `Depends`/`MakeDepends` arrays, so no separate `depends` lookup is needed.
+const AUR_RPC_URL: &str = "https://aur.archlinux.org/rpc/v5/info";
+
+/// A dependency resolution, ready to hand to the rest of the builder: repo
+/// packages can be installed directly, AUR packages need to be cloned and
+/// built first, in the given order (dependencies before dependents).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyPlan {
+    /// Official-repo packages, installable directly via the package manager
+    pub repo_packages: Vec<String>,
+    /// AUR packages in build order (a package's own dependencies precede it)
+    pub aur_packages: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AurRpcResponse {
+    results: Vec<AurRpcPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AurRpcPackage {
+    #[serde(rename = "Depends", default)]
+    depends: Vec<String>,
+    #[serde(rename = "MakeDepends", default)]
+    make_depends: Vec<String>,
+}
+
+/// Resolves a flat dependency list into a [`DependencyPlan`] by transitively
+/// expanding AUR package dependencies through the AUR RPC.
+pub struct AurResolver<'a> {
+    process_runner: &'a ProcessRunner,
+}
+
+impl<'a> AurResolver<'a> {
+    /// Create a resolver that checks official-repo availability through `process_runner`
+    #[must_use]
+    pub fn new(process_runner: &'a ProcessRunner) -> Self {
+        Self { process_runner }
+    }
+
+    /// Transitively resolve `root_deps`. Any name not found in the official
+    /// repos is queried against the AUR RPC and its own dependencies are
+    /// expanded in turn. Returns a `BuilderError::dependency` listing every
+    /// name that couldn't be found in either place, or naming the package
+    /// at which a dependency cycle was detected.
+    #[instrument(skip(self, root_deps))]
+    pub fn resolve(&self, root_deps: &[String]) -> Result<DependencyPlan> {
+        let mut repo_packages = Vec::new();
+        let mut aur_depends: HashMap<String, Vec<String>> = HashMap::new();
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut missing: Vec<String> = Vec::new();
+        let mut queue: Vec<String> = root_deps.to_vec();
+
+        while let Some(name) = queue.pop() {
+            let name = strip_version_constraint(&name);
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+
+            if self.is_in_official_repos(&name) {
+                repo_packages.push(name);
+                continue;
+            }
+
+            match self.fetch_aur_package(&name) {
+                Ok(Some(pkg)) => {
+                    let deps: Vec<String> = pkg
+                        .depends
+                        .iter()
+                        .chain(pkg.make_depends.iter())
+                        .map(|d| strip_version_constraint(d))
+                        .collect();
+                    queue.extend(deps.iter().cloned());
+                    aur_depends.insert(name, deps);
+                }
+                Ok(None) => missing.push(name),
+                Err(e) => {
+                    warn!("AUR lookup failed for {}: {}", name, e);
+                    missing.push(name);
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            return Err(BuilderError::dependency(
+                format!("Could not resolve dependencies: {}", missing.join(", ")),
+                missing,
+            ));
+        }
+
+        let aur_packages = topological_sort(&aur_depends)?;
+        repo_packages.sort();
+
+        Ok(DependencyPlan {
+            repo_packages,
+            aur_packages,
+        })
+    }
+
+    /// Check whether `name` is available in the official repos
+    fn is_in_official_repos(&self, name: &str) -> bool {
+        self.process_runner
+            .command("pacman")
+            .arg("-Si")
+            .arg(name)
+            .inherit_stdio(false)
+            .status()
+            .is_ok()
+    }
+
+    /// Query the AUR RPC `info` endpoint for a single package
+    fn fetch_aur_package(&self, name: &str) -> Result<Option<AurRpcPackage>> {
+        let url = format!("{AUR_RPC_URL}?arg[]={name}");
+        debug!("Querying AUR RPC: {}", url);
+
+        let response: AurRpcResponse = ureq::get(&url)
+            .call()
+            .map_err(|e| {
+                BuilderError::dependency(format!("AUR RPC request failed: {e}"), vec![name.to_string()])
+            })?
+            .into_json()
+            .map_err(|e| {
+                BuilderError::dependency(format!("Invalid AUR RPC response: {e}"), vec![name.to_string()])
+            })?;
+
+        Ok(response.results.into_iter().next())
+    }
+}
+
+/// Strip a version constraint (`foo>=1.0`, `foo=1.0`, `foo<1.0`) from a
+/// dependency string, leaving just the package name.
+fn strip_version_constraint(dep: &str) -> String {
+    dep.split(['<', '>', '=']).next().unwrap_or(dep).to_string()
+}
+
+/// Depth-first postorder topological sort over the AUR dependency graph, so
+/// every package's dependencies are emitted before the package itself.
+/// Detects cycles instead of looping forever.
+fn topological_sort(depends: &HashMap<String, Vec<String>>) -> Result<Vec<String>> {
+    enum State {
+        Visiting,
+        Done,
+    }
+
+    fn visit(
+        name: &str,
+        depends: &HashMap<String, Vec<String>>,
+        state: &mut HashMap<String, State>,
+        order: &mut Vec<String>,
+    ) -> Result<()> {
+        match state.get(name) {
+            Some(State::Done) => return Ok(()),
+            Some(State::Visiting) => {
+                return Err(BuilderError::dependency(
+                    format!("Dependency cycle detected involving '{name}'"),
+                    vec![name.to_string()],
+                ));
+            }
+            None => {}
+        }
+
+        state.insert(name.to_string(), State::Visiting);
+        if let Some(deps) = depends.get(name) {
+            for dep in deps {
+                if depends.contains_key(dep) {
+                    visit(dep, depends, state, order)?;
+                }
+            }
+        }
+        state.insert(name.to_string(), State::Done);
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    let mut state = HashMap::new();
+    let mut order = Vec::new();
+
+    let mut names: Vec<&String> = depends.keys().collect();
+    names.sort();
+    for name in names {
+        visit(name, depends, &mut state, &mut order)?;
+    }
+
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_version_constraint() {
+        assert_eq!(strip_version_constraint("foo>=1.0"), "foo");
+        assert_eq!(strip_version_constraint("foo=1.0"), "foo");
+        assert_eq!(strip_version_constraint("foo<2.0"), "foo");
+        assert_eq!(strip_version_constraint("foo"), "foo");
+    }
+
+    #[test]
+    fn test_topological_sort_orders_dependencies_first() {
+        let mut depends = HashMap::new();
+        depends.insert("a".to_string(), vec!["b".to_string()]);
+        depends.insert("b".to_string(), vec!["c".to_string()]);
+        depends.insert("c".to_string(), vec![]);
+
+        let order = topological_sort(&depends).unwrap();
+        let pos = |name: &str| order.iter().position(|n| n == name).unwrap();
+
+        assert!(pos("c") < pos("b"));
+        assert!(pos("b") < pos("a"));
+    }
+
+    #[test]
+    fn test_topological_sort_detects_cycle() {
+        let mut depends = HashMap::new();
+        depends.insert("a".to_string(), vec!["b".to_string()]);
+        depends.insert("b".to_string(), vec!["a".to_string()]);
+
+        assert!(topological_sort(&depends).is_err());
+    }
+}
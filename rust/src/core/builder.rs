@@ -4,46 +4,152 @@
 
 use crate::{
     config::Config,
-    core::pkgbuild::PkgbuildInfo,
+    core::{
+        aur::AurResolver,
+        depends::Dependency,
+        makepkg::MakePkgOptions,
+        pkgbuild::{PkgbuildInfo, PkgbuildParser},
+        workcache::WorkCache,
+    },
     error::{BuilderError, Result},
-    utils::process::ProcessRunner,
+    utils::{
+        events,
+        fs::FileSystemUtils,
+        process::{PlannedInvocation, ProcessRunner},
+        transaction::Transaction,
+    },
 };
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use tracing::{debug, info, instrument, warn};
 
 /// Package builder that handles dependencies and compilation
 pub struct PackageBuilder {
     config: Config,
     process_runner: ProcessRunner,
+    /// Recorded invocations when `config.build.dry_run` is set
+    plan: Option<Arc<Mutex<Vec<PlannedInvocation>>>>,
+    /// Serializes the workcache file's load-mutate-save cycle in
+    /// [`Self::build_in`], since [`Self::build_many`] runs several of those
+    /// concurrently against the same workcache file path.
+    workcache_lock: Arc<Mutex<()>>,
+    /// Canonicalized paths of local-path dependencies currently being built,
+    /// so [`Self::build_local_dependency`] can detect a cyclic `_localdepends`
+    /// graph instead of recursing until the stack overflows.
+    in_progress_local_deps: HashSet<PathBuf>,
+}
+
+/// A single package queued for [`PackageBuilder::build_many`]: its parsed
+/// PKGBUILD, the directory containing it, and the makepkg flags to build it
+/// with. Each job builds in its own `work_dir` so concurrent jobs never
+/// collide over `*.pkg.tar.*` glob discovery.
+#[derive(Debug, Clone)]
+pub struct BuildJob {
+    /// Directory containing this package's PKGBUILD
+    pub work_dir: PathBuf,
+    /// Parsed PKGBUILD metadata
+    pub pkgbuild: PkgbuildInfo,
+    /// makepkg/paru flags for this invocation
+    pub options: MakePkgOptions,
+}
+
+/// Aggregate result of [`PackageBuilder::build_many`]: per-package outcomes
+/// rather than a single pass/fail for the whole batch, so a handful of
+/// broken PKGBUILDs don't stop the rest from building.
+#[derive(Debug, Default)]
+pub struct BuildManyReport {
+    /// Packages that built successfully, with their generated package files
+    pub succeeded: Vec<(String, Vec<PathBuf>)>,
+    /// Packages that failed, with the error each one hit
+    pub failed: Vec<(String, BuilderError)>,
+}
+
+impl BuildManyReport {
+    /// Short human summary, e.g. `"7 of 9 built, 2 failed"`
+    #[must_use]
+    pub fn summary(&self) -> String {
+        format!(
+            "{} of {} built, {} failed",
+            self.succeeded.len(),
+            self.succeeded.len() + self.failed.len(),
+            self.failed.len()
+        )
+    }
 }
 
 impl PackageBuilder {
     /// Create a new package builder with the given configuration
     #[must_use]
-    pub const fn new(config: Config) -> Self {
+    pub fn new(config: Config) -> Self {
+        let (mut process_runner, plan) = if config.build.dry_run {
+            let (runner, plan) = ProcessRunner::new_dry_run(config.debug);
+            (runner, Some(plan))
+        } else {
+            (ProcessRunner::new(config.debug), None)
+        };
+
+        if let Some(tool) = &config.package_manager.escalation {
+            process_runner = process_runner.with_escalation(tool.clone());
+        }
+        process_runner = process_runner.with_event_sink(events::sink_for(config.json_events));
+
         Self {
-            process_runner: ProcessRunner::new(config.debug),
+            process_runner,
+            plan,
             config,
+            workcache_lock: Arc::new(Mutex::new(())),
+            in_progress_local_deps: HashSet::new(),
         }
     }
 
-    /// Install dependencies for the given PKGBUILD
+    /// Commands recorded instead of executed in dry-run mode, in invocation order.
+    /// Empty when `config.build.dry_run` is not set.
+    #[must_use]
+    pub fn planned_invocations(&self) -> Vec<PlannedInvocation> {
+        self.plan
+            .as_ref()
+            .map(|plan| plan.lock().expect("planned invocation list poisoned").clone())
+            .unwrap_or_default()
+    }
+
+    /// Install dependencies for the given PKGBUILD. Repo packages are
+    /// installed through the configured package manager as before;
+    /// local-path dependencies are built recursively and their output
+    /// installed directly, and native-library dependencies are verified
+    /// present rather than installed. Local/native dependencies are resolved
+    /// before the repo install, so a locally-built package is already
+    /// available if a repo dependency (indirectly) needs it.
     #[instrument(skip(self, pkgbuild))]
     pub fn install_dependencies(&mut self, pkgbuild: &PkgbuildInfo) -> Result<()> {
-        let all_deps = pkgbuild.all_dependencies();
+        let typed_deps = pkgbuild.typed_dependencies();
 
-        if all_deps.is_empty() {
+        if typed_deps.is_empty() {
             info!("No dependencies found in PKGBUILD");
             return Ok(());
         }
 
-        info!("Found {} dependencies: {:?}", all_deps.len(), all_deps);
+        info!("Found {} dependencies: {:?}", typed_deps.len(), typed_deps);
+
+        let mut repo_deps = Vec::new();
+        for dep in typed_deps {
+            match dep {
+                Dependency::Repo(name) => repo_deps.push(name),
+                Dependency::LocalPath(path) => self.build_local_dependency(&path)?,
+                Dependency::Native(lib) => self.verify_native_dependency(&lib)?,
+            }
+        }
+
+        if repo_deps.is_empty() {
+            info!("All dependencies were locally-built or native-library checks");
+            return Ok(());
+        }
 
         // Handle rust/rustup conflicts if enabled
         let filtered_deps = if self.config.package_manager.handle_rust_conflict {
-            self.handle_rust_conflict(all_deps)
+            self.handle_rust_conflict(repo_deps)
         } else {
-            all_deps
+            repo_deps
         };
 
         if filtered_deps.is_empty() {
@@ -51,6 +157,24 @@ impl PackageBuilder {
             return Ok(());
         }
 
+        if self.config.package_manager.resolve_aur_deps {
+            let plan = AurResolver::new(&self.process_runner).resolve(&filtered_deps)?;
+
+            if !plan.aur_packages.is_empty() {
+                return Err(BuilderError::dependency(
+                    format!(
+                        "The following AUR packages must be built before this one, in order: {}. \
+                         This builder only compiles a single prepared PKGBUILD; clone and build \
+                         them first (e.g. with `build_many`), then retry.",
+                        plan.aur_packages.join(", ")
+                    ),
+                    plan.aur_packages,
+                ));
+            }
+
+            return self.install_package_list(&plan.repo_packages);
+        }
+
         self.install_package_list(&filtered_deps)
     }
 
@@ -83,6 +207,98 @@ impl PackageBuilder {
         filtered_deps
     }
 
+    /// Build a sibling PKGBUILD found at `path` and install its resulting
+    /// package files directly, so it satisfies a `LocalPath` dependency the
+    /// same way a repo package satisfies a `Repo` one. Its own dependencies
+    /// (including further `LocalPath`/`Native` ones) are resolved first,
+    /// which gives dependency-before-dependent ordering for free through
+    /// recursion rather than a separate topological sort. `path` is tracked
+    /// while its build is in progress (like the `Visiting` state in
+    /// `aur.rs`'s `topological_sort`) so a cyclic `_localdepends` graph is
+    /// reported as a dependency error instead of recursing until the stack
+    /// overflows.
+    fn build_local_dependency(&mut self, path: &Path) -> Result<()> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        if !self.in_progress_local_deps.insert(canonical.clone()) {
+            return Err(BuilderError::dependency(
+                format!(
+                    "Dependency cycle detected: local-path dependency '{}' depends (transitively) on itself",
+                    path.display()
+                ),
+                vec![path.display().to_string()],
+            ));
+        }
+
+        let result = self.build_local_dependency_inner(path);
+        self.in_progress_local_deps.remove(&canonical);
+        result
+    }
+
+    /// The actual build behind [`Self::build_local_dependency`], split out so
+    /// the cycle-tracking insert/remove always runs regardless of outcome.
+    fn build_local_dependency_inner(&mut self, path: &Path) -> Result<()> {
+        info!("Building local-path dependency: {}", path.display());
+
+        let parser = PkgbuildParser::new()?;
+        let local_pkgbuild = parser.parse(path.join(&self.config.pkgbuild_path))?;
+
+        self.install_dependencies(&local_pkgbuild)?;
+        let package_files = self.build_in(path, &local_pkgbuild, &MakePkgOptions::default())?;
+
+        self.install_local_packages(&package_files)
+    }
+
+    /// Install already-built package files directly (`pacman -U`), making a
+    /// locally-built dependency available the same way `install_package_list`
+    /// makes a repo one available.
+    fn install_local_packages(&self, package_files: &[PathBuf]) -> Result<()> {
+        if package_files.is_empty() {
+            return Ok(());
+        }
+
+        let files: Vec<String> = package_files
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+
+        info!("Installing locally-built package(s): {:?}", files);
+
+        let mut args = vec!["-U".to_string(), "--noconfirm".to_string(), "--needed".to_string()];
+        args.extend(files.iter().cloned());
+
+        self.process_runner
+            .command("pacman")
+            .args(args)
+            .elevated()
+            .status()
+            .map_err(|e| {
+                BuilderError::dependency(format!("Failed to install locally-built package(s): {e}"), files)
+            })
+    }
+
+    /// Verify a native/system library is present via `pkg-config --exists`,
+    /// falling back to scanning `ldconfig -p` if `pkg-config` doesn't know
+    /// about it (common for libraries without a `.pc` file).
+    fn verify_native_dependency(&self, lib: &str) -> Result<()> {
+        if self.process_runner.command("pkg-config").arg("--exists").arg(lib).status().is_ok() {
+            debug!("Native dependency '{}' found via pkg-config", lib);
+            return Ok(());
+        }
+
+        if let Ok(result) = self.process_runner.command("ldconfig").arg("-p").output() {
+            if result.stdout.lines().any(|line| line.contains(lib)) {
+                debug!("Native dependency '{}' found via ldconfig", lib);
+                return Ok(());
+            }
+        }
+
+        Err(BuilderError::dependency(
+            format!("Native dependency '{lib}' not found (checked pkg-config and ldconfig)"),
+            vec![lib.to_string()],
+        ))
+    }
+
     /// Install a list of packages using the configured package manager
     #[instrument(skip(self, packages))]
     fn install_package_list(&self, packages: &[String]) -> Result<()> {
@@ -92,8 +308,11 @@ impl PackageBuilder {
         info!("Installing packages with {}: {:?}", cmd, packages);
 
         // Try primary package manager first
-        let args_str: Vec<&str> = args.iter().map(String::as_str).collect();
-        match self.process_runner.run_command(&cmd, &args_str) {
+        let mut command = self.process_runner.command(&cmd).args(args);
+        if let Some(timeout) = self.config.build.timeout {
+            command = command.timeout(timeout);
+        }
+        match command.status() {
             Ok(()) => {
                 info!("Successfully installed dependencies with {}", cmd);
                 Ok(())
@@ -118,20 +337,17 @@ impl PackageBuilder {
         }
     }
 
-    /// Try installing with fallback package manager (usually pacman with sudo)
+    /// Try installing with fallback package manager (usually pacman, run elevated)
     fn try_fallback_installation(&self, fallback: &str, packages: &[String]) -> Result<()> {
-        let mut args = vec![fallback];
-        args.extend(
-            self.config
-                .package_manager
-                .install_args
-                .iter()
-                .map(String::as_str),
-        );
-        let package_strs: Vec<&str> = packages.iter().map(String::as_str).collect();
-        args.extend(package_strs);
+        let mut args = self.config.package_manager.install_args.clone();
+        args.extend(packages.iter().cloned());
+
+        let mut command = self.process_runner.command(fallback).args(args).elevated();
+        if let Some(timeout) = self.config.build.timeout {
+            command = command.timeout(timeout);
+        }
 
-        match self.process_runner.run_command("sudo", &args) {
+        match command.status() {
             Ok(()) => {
                 info!("Successfully installed dependencies with {}", fallback);
                 Ok(())
@@ -151,9 +367,15 @@ impl PackageBuilder {
     pub fn clean(&self) -> Result<()> {
         info!("Cleaning previous build artifacts");
 
+        // `build`/`build_in` run makepkg with `self.config.work_dir` as its
+        // cwd, so that's where package files and `src`/`pkg` end up — clean
+        // against the same directory, not the process's cwd, so `--clean`
+        // still targets the right place when the two differ.
+        let work_dir = &self.config.work_dir;
+
         // Remove package files
-        let pkg_pattern = "*.pkg.tar.*";
-        if let Ok(paths) = glob::glob(pkg_pattern) {
+        let pkg_pattern = work_dir.join("*.pkg.tar.*");
+        if let Ok(paths) = glob::glob(&pkg_pattern.to_string_lossy()) {
             for path in paths.flatten() {
                 if let Err(e) = std::fs::remove_file(&path) {
                     warn!("Failed to remove {}: {}", path.display(), e);
@@ -163,42 +385,178 @@ impl PackageBuilder {
             }
         }
 
-        // Remove build directories
+        // Remove build directories. Guarded against symlinks pointing
+        // outside the working directory, and reports per-entry failures
+        // instead of aborting the whole clean on the first one.
+        let fs_utils = FileSystemUtils::new();
         for dir in &["src", "pkg"] {
-            if let Err(e) = std::fs::remove_dir_all(dir) {
-                debug!("Could not remove directory {} (may not exist): {}", dir, e);
-            } else {
+            let failures = fs_utils.remove_dir_all_guarded(work_dir.join(dir), work_dir);
+            if failures.is_empty() {
                 debug!("Removed directory: {}", dir);
+            } else {
+                for (path, e) in failures {
+                    warn!("Failed to remove {}: {}", path.display(), e);
+                }
             }
         }
 
         Ok(())
     }
 
-    /// Build the package using paru
+    /// Build the package using paru, with `options` controlling which
+    /// makepkg/paru flags (clean build, dependency-resolution-only, skipped
+    /// phases, ...) are appended to the invocation.
     #[instrument(skip(self, pkgbuild))]
-    pub fn build(&self, pkgbuild: &PkgbuildInfo) -> Result<Vec<PathBuf>> {
-        info!("Building package: {}", pkgbuild.name);
+    pub fn build(&self, pkgbuild: &PkgbuildInfo, options: &MakePkgOptions) -> Result<Vec<PathBuf>> {
+        let work_dir = self.config.work_dir.clone();
+        self.build_in(&work_dir, pkgbuild, options)
+    }
+
+    /// Build multiple independent packages. Dependency installation is
+    /// serialized first (package managers take a shared lock, so running
+    /// those concurrently would just queue behind each other anyway); the
+    /// compile step for up to `max_workers` packages then runs concurrently,
+    /// each confined to its own `job.work_dir`. A failing package is
+    /// recorded in the report's `failed` list rather than aborting the rest
+    /// of the batch.
+    #[instrument(skip(self, jobs))]
+    pub fn build_many(&mut self, jobs: Vec<BuildJob>, max_workers: usize) -> BuildManyReport {
+        let max_workers = max_workers.max(1);
+
+        for job in &jobs {
+            if let Err(e) = self.install_dependencies(&job.pkgbuild) {
+                warn!(
+                    "Dependency installation failed for {}: {}",
+                    job.pkgbuild.name, e
+                );
+            }
+        }
 
-        let (cmd, args) = self.config.get_build_cmd();
-        let args_str: Vec<&str> = args.iter().map(String::as_str).collect();
-        let mut env_vars = Vec::new();
+        let this: &Self = self;
+        let mut report = BuildManyReport::default();
+
+        for chunk in jobs.chunks(max_workers) {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = chunk
+                    .iter()
+                    .map(|job| {
+                        scope.spawn(move || {
+                            let name = job.pkgbuild.name.clone();
+                            let result = this.build_in(&job.work_dir, &job.pkgbuild, &job.options);
+                            (name, result)
+                        })
+                    })
+                    .collect();
+
+                for handle in handles {
+                    match handle.join() {
+                        Ok((name, Ok(files))) => report.succeeded.push((name, files)),
+                        Ok((name, Err(e))) => report.failed.push((name, e)),
+                        Err(_) => report.failed.push((
+                            "<unknown>".to_string(),
+                            BuilderError::build("Build thread panicked"),
+                        )),
+                    }
+                }
+            });
+        }
+
+        info!("build_many finished: {}", report.summary());
+        report
+    }
+
+    /// Core of [`Self::build`], parameterized over the working directory so
+    /// [`Self::build_many`] can run several of these concurrently without
+    /// their `*.pkg.tar.*` glob discovery colliding.
+    fn build_in(
+        &self,
+        work_dir: &Path,
+        pkgbuild: &PkgbuildInfo,
+        options: &MakePkgOptions,
+    ) -> Result<Vec<PathBuf>> {
+        info!("Building package: {} in {}", pkgbuild.name, work_dir.display());
+
+        let workcache_path = self
+            .config
+            .artifacts
+            .output_dir
+            .join(crate::core::workcache::WORKCACHE_FILE);
+        let mut workcache = WorkCache::load(&workcache_path);
+
+        // Inputs are the PKGBUILD itself plus any `source=()` entries that
+        // resolve to a local file already present in `work_dir`; remote
+        // URLs aren't trackable without re-fetching them.
+        let mut input_paths = vec![work_dir.join(&self.config.pkgbuild_path)];
+        input_paths.extend(
+            pkgbuild
+                .source
+                .iter()
+                .map(|source| work_dir.join(source))
+                .filter(|path| path.exists()),
+        );
+        let inputs = WorkCache::signatures(&input_paths);
+
+        if self.config.build.clean {
+            // A requested clean invalidates any cached record: the outputs
+            // it pointed at were just removed, and a fresh build is coming.
+            workcache.invalidate(&pkgbuild.name);
+        } else if !self.config.build.dry_run {
+            if let Some(cached_files) = workcache.is_fresh(&pkgbuild.name, &inputs) {
+                info!(
+                    "{} is fresh (inputs unchanged since last build), skipping",
+                    pkgbuild.name
+                );
+                return Ok(cached_files);
+            }
+        }
+
+        // Snapshot what's already on disk so a failed build only rolls back
+        // package files *this* invocation produced, never pre-existing ones.
+        let pre_existing = Self::find_package_files(work_dir).unwrap_or_default();
+        let mut tx = Transaction::new();
+
+        let (cmd, mut args) = self.config.get_build_cmd();
+        args.extend(options.to_args());
+        let mut command = self
+            .process_runner
+            .command(&cmd)
+            .args(args)
+            .cwd(work_dir.to_path_buf());
 
         // Set ccache environment if enabled
         if self.config.build.use_ccache {
-            env_vars.push((
-                "CCACHE_DIR".to_string(),
+            command = command.env(
+                "CCACHE_DIR",
                 self.config.build.ccache_dir.to_string_lossy().to_string(),
-            ));
+            );
         }
 
-        // Execute build command
-        self.process_runner
-            .run_command_with_env(&cmd, &args_str, &env_vars)
-            .map_err(|e| BuilderError::build(format!("Package build failed: {e}")))?;
+        if let Some(timeout) = self.config.build.timeout {
+            command = command.timeout(timeout);
+        }
+
+        // Execute build command, tracking any new package files regardless
+        // of outcome so a mid-build failure still rolls back partial output.
+        let build_result = command.status();
+
+        if let Ok(post_build_files) = Self::find_package_files(work_dir) {
+            for path in &post_build_files {
+                if !pre_existing.contains(path) {
+                    tx.track_created(path.clone());
+                }
+            }
+        }
+
+        build_result.map_err(|e| BuilderError::build(format!("Package build failed: {e}")))?;
+
+        if self.config.build.dry_run {
+            info!("Dry run: skipping package file discovery");
+            tx.commit();
+            return Ok(Vec::new());
+        }
 
         // Find generated package files
-        let package_files = Self::find_package_files()?;
+        let package_files = Self::find_package_files(work_dir)?;
 
         if package_files.is_empty() {
             return Err(BuilderError::build(
@@ -220,15 +578,33 @@ impl PackageBuilder {
         // List generated files for verification
         self.list_package_files(&package_files);
 
+        // Re-load under the lock (rather than reusing the copy loaded at the
+        // top of this call) so a concurrent `build_many` job's record that
+        // landed in between isn't clobbered by this save.
+        let outputs = WorkCache::signatures(&package_files);
+        {
+            let _guard = self
+                .workcache_lock
+                .lock()
+                .expect("workcache lock poisoned");
+            let mut workcache = WorkCache::load(&workcache_path);
+            workcache.record(pkgbuild.name.clone(), inputs, outputs);
+            if let Err(e) = workcache.save(&workcache_path) {
+                warn!("Failed to update workcache: {e}");
+            }
+        }
+
+        tx.commit();
         Ok(package_files)
     }
 
-    /// Find generated package files
-    fn find_package_files() -> Result<Vec<PathBuf>> {
+    /// Find generated package files in `dir`
+    fn find_package_files(dir: &Path) -> Result<Vec<PathBuf>> {
         let mut package_files = Vec::new();
-        let pkg_pattern = "*.pkg.tar.*";
+        let pkg_pattern = dir.join("*.pkg.tar.*");
+        let pkg_pattern = pkg_pattern.to_string_lossy();
 
-        match glob::glob(pkg_pattern) {
+        match glob::glob(&pkg_pattern) {
             Ok(paths) => {
                 for path_result in paths {
                     match path_result {
@@ -254,11 +630,19 @@ impl PackageBuilder {
 
     /// List package files with details
     fn list_package_files(&self, package_files: &[PathBuf]) {
-        let mut args = vec!["-la"];
-        let file_strs: Vec<&str> = package_files.iter().filter_map(|p| p.to_str()).collect();
-        args.extend(file_strs);
-
-        if let Err(e) = self.process_runner.run_command("ls", &args) {
+        let file_strs: Vec<String> = package_files
+            .iter()
+            .filter_map(|p| p.to_str().map(String::from))
+            .collect();
+
+        let result = self
+            .process_runner
+            .command("ls")
+            .arg("-la")
+            .args(file_strs)
+            .status();
+
+        if let Err(e) = result {
             warn!("Could not list package files: {e}");
         }
     }
@@ -277,6 +661,8 @@ mod tests {
             package_manager: PackageManagerConfig::default(),
             build: BuildConfig::default(),
             artifacts: crate::config::ArtifactConfig::default(),
+            json_events: false,
+            aliases: std::collections::HashMap::new(),
         }
     }
 
@@ -306,4 +692,16 @@ mod tests {
         let builder = PackageBuilder::new(config);
         assert!(!builder.config.build.clean);
     }
+
+    #[test]
+    fn test_build_many_report_summary() {
+        let mut report = BuildManyReport::default();
+        report.succeeded.push(("ok-one".to_string(), vec![]));
+        report.succeeded.push(("ok-two".to_string(), vec![]));
+        report
+            .failed
+            .push(("broken".to_string(), BuilderError::build("boom")));
+
+        assert_eq!(report.summary(), "2 of 3 built, 1 failed");
+    }
 }
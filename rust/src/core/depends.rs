@@ -0,0 +1,22 @@
+//! Typed dependency declarations
+//!
+//! A plain `Vec<String>` of package names can't express anything beyond "ask
+//! the package manager for this"; [`Dependency`] adds the two other shapes a
+//! PKGBUILD in this pipeline can declare: a sibling PKGBUILD that needs
+//! building first, and a system/C library whose presence is probed for
+//! rather than installed.
+
+use std::path::PathBuf;
+
+/// A single typed dependency, as resolved by [`crate::core::builder::PackageBuilder::install_dependencies`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dependency {
+    /// An official-repo package name, installed via the configured package manager
+    Repo(String),
+    /// A sibling PKGBUILD to build first; its output packages are installed
+    /// directly instead of going through the package manager
+    LocalPath(PathBuf),
+    /// A system/C library whose presence is probed for (`pkg-config`,
+    /// falling back to `ldconfig`) rather than installed
+    Native(String),
+}
@@ -0,0 +1,161 @@
+//! makepkg/paru invocation options
+//!
+//! A fluent builder for the handful of makepkg/paru flags `PackageBuilder`
+//! needs to toggle per invocation (clean builds, dependency-resolution-only
+//! runs, skipping phases), so callers aren't limited to the single static
+//! argument list in `BuildConfig`.
+
+/// Chainable makepkg/paru flags for a single build invocation. Every flag
+/// defaults to off; [`MakePkgOptions::to_args`] returns only the ones set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MakePkgOptions {
+    clean: bool,
+    no_deps: bool,
+    install: bool,
+    no_build: bool,
+    no_prepare: bool,
+    skip_pgp: bool,
+    needed: bool,
+    as_deps: bool,
+}
+
+impl MakePkgOptions {
+    /// Start with every flag off
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `-C`: remove `$srcdir`/`$pkgdir` before building
+    #[must_use]
+    pub const fn clean(mut self, clean: bool) -> Self {
+        self.clean = clean;
+        self
+    }
+
+    /// `-d`: skip all dependency checks
+    #[must_use]
+    pub const fn no_deps(mut self, no_deps: bool) -> Self {
+        self.no_deps = no_deps;
+        self
+    }
+
+    /// `-i`: install the package after a successful build
+    #[must_use]
+    pub const fn install(mut self, install: bool) -> Self {
+        self.install = install;
+        self
+    }
+
+    /// `--nobuild`: download and extract sources only, do not build
+    #[must_use]
+    pub const fn no_build(mut self, no_build: bool) -> Self {
+        self.no_build = no_build;
+        self
+    }
+
+    /// `--noprepare`: do not run the `prepare()` function
+    #[must_use]
+    pub const fn no_prepare(mut self, no_prepare: bool) -> Self {
+        self.no_prepare = no_prepare;
+        self
+    }
+
+    /// `--skippgpcheck`: do not verify source file PGP signatures
+    #[must_use]
+    pub const fn skip_pgp(mut self, skip_pgp: bool) -> Self {
+        self.skip_pgp = skip_pgp;
+        self
+    }
+
+    /// `--needed`: do not reinstall an up-to-date package
+    #[must_use]
+    pub const fn needed(mut self, needed: bool) -> Self {
+        self.needed = needed;
+        self
+    }
+
+    /// `--asdeps`: install the package as a dependency of another package
+    #[must_use]
+    pub const fn as_deps(mut self, as_deps: bool) -> Self {
+        self.as_deps = as_deps;
+        self
+    }
+
+    /// Render the flags that are set as makepkg/paru CLI arguments
+    #[must_use]
+    pub fn to_args(self) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if self.clean {
+            args.push("-C".to_string());
+        }
+        if self.no_deps {
+            args.push("-d".to_string());
+        }
+        if self.install {
+            args.push("-i".to_string());
+        }
+        if self.no_build {
+            args.push("--nobuild".to_string());
+        }
+        if self.no_prepare {
+            args.push("--noprepare".to_string());
+        }
+        if self.skip_pgp {
+            args.push("--skippgpcheck".to_string());
+        }
+        if self.needed {
+            args.push("--needed".to_string());
+        }
+        if self.as_deps {
+            args.push("--asdeps".to_string());
+        }
+
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_has_no_args() {
+        assert!(MakePkgOptions::new().to_args().is_empty());
+    }
+
+    #[test]
+    fn test_chained_flags_render_in_order() {
+        let args = MakePkgOptions::new()
+            .clean(true)
+            .no_deps(true)
+            .install(true)
+            .no_build(true)
+            .no_prepare(true)
+            .skip_pgp(true)
+            .needed(true)
+            .as_deps(true)
+            .to_args();
+
+        assert_eq!(
+            args,
+            vec![
+                "-C",
+                "-d",
+                "-i",
+                "--nobuild",
+                "--noprepare",
+                "--skippgpcheck",
+                "--needed",
+                "--asdeps",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only_set_flags_are_included() {
+        let args = MakePkgOptions::new().no_prepare(true).to_args();
+        assert_eq!(args, vec!["--noprepare"]);
+    }
+}
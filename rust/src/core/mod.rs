@@ -4,9 +4,21 @@
 //! and collecting artifacts.
 
 pub mod artifacts;
+pub mod aur;
 pub mod builder;
+pub mod depends;
+pub mod makepkg;
+pub mod pipeline;
 pub mod pkgbuild;
+pub mod version;
+pub mod workcache;
 
 pub use artifacts::ArtifactCollector;
-pub use builder::PackageBuilder;
-pub use pkgbuild::{PkgbuildInfo, PkgbuildParser};
+pub use aur::{AurResolver, DependencyPlan};
+pub use builder::{BuildJob, BuildManyReport, PackageBuilder};
+pub use depends::Dependency;
+pub use makepkg::MakePkgOptions;
+pub use pipeline::{Phase, PhaseRange, PipelineReport};
+pub use pkgbuild::{DependencySpec, PkgbuildInfo, PkgbuildParser, VersionConstraint};
+pub use version::compare_versions;
+pub use workcache::{FileSignature, WorkCache};
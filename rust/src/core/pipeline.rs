@@ -0,0 +1,130 @@
+//! Phased build pipeline
+//!
+//! Models the build process as an ordered sequence of phases — deps, build,
+//! artifacts, version — and drives any contiguous sub-range of them against
+//! a single parsed [`PkgbuildInfo`] and shared [`Config`], instead of
+//! re-parsing the PKGBUILD for every separate CLI invocation.
+//! `builder pipeline --from deps --to artifacts` installs dependencies,
+//! builds, and collects artifacts in one pass while skipping version-file
+//! generation.
+
+use crate::{
+    config::Config,
+    core::{
+        artifacts::{ArtifactCollector, CollectedArtifact},
+        builder::PackageBuilder,
+        makepkg::MakePkgOptions,
+        pkgbuild::{PkgbuildInfo, PkgbuildParser},
+    },
+    error::{BuilderError, Result},
+    utils::env::VersionGenerator,
+};
+use std::path::PathBuf;
+use tracing::info;
+
+/// A single stage of the build pipeline, in execution order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Phase {
+    /// Parse the PKGBUILD and install its dependencies
+    Deps,
+    /// Build the package
+    Build,
+    /// Collect build artifacts
+    Artifacts,
+    /// Generate version information
+    Version,
+}
+
+/// A validated, contiguous `[from, to]` sub-range of [`Phase`]s to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseRange {
+    pub from: Phase,
+    pub to: Phase,
+}
+
+impl PhaseRange {
+    /// Build a range, rejecting one where `from` comes after `to`
+    pub fn new(from: Phase, to: Phase) -> Result<Self> {
+        if from > to {
+            return Err(BuilderError::validation(format!(
+                "Pipeline range is backwards: --from {from:?} comes after --to {to:?}"
+            )));
+        }
+        Ok(Self { from, to })
+    }
+
+    fn includes(self, phase: Phase) -> bool {
+        phase >= self.from && phase <= self.to
+    }
+}
+
+/// What each executed phase produced, for phases that weren't skipped
+#[derive(Debug, Default)]
+pub struct PipelineReport {
+    /// Package files from the `Build` phase, if it ran
+    pub package_files: Vec<PathBuf>,
+    /// Collected artifacts from the `Artifacts` phase, if it ran
+    pub collected_artifacts: Vec<CollectedArtifact>,
+}
+
+/// Drive `range` against a single parsed PKGBUILD and shared `config`,
+/// short-circuiting before `range.from` and stopping after `range.to`.
+pub fn run(config: &Config, range: PhaseRange) -> Result<PipelineReport> {
+    let parser = PkgbuildParser::new()?;
+    let pkgbuild: PkgbuildInfo = parser.parse(&config.pkgbuild_path)?;
+
+    let mut report = PipelineReport::default();
+    let mut builder = PackageBuilder::new(config.clone());
+
+    if range.includes(Phase::Deps) {
+        info!("Pipeline: installing dependencies");
+        builder.install_dependencies(&pkgbuild)?;
+    }
+
+    if range.includes(Phase::Build) {
+        info!("Pipeline: building package");
+        if config.build.clean {
+            builder.clean()?;
+        }
+        report.package_files = builder.build(&pkgbuild, &MakePkgOptions::default())?;
+    }
+
+    if range.includes(Phase::Artifacts) {
+        info!("Pipeline: collecting artifacts");
+        let collector = ArtifactCollector::new(config.clone());
+        report.collected_artifacts = collector.collect()?;
+    }
+
+    if range.includes(Phase::Version) {
+        info!("Pipeline: generating version information");
+        let generator = VersionGenerator::new();
+        generator.generate(&pkgbuild, &config.artifacts.version_file)?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_range_accepts_forward_order() {
+        let range = PhaseRange::new(Phase::Deps, Phase::Artifacts).unwrap();
+        assert!(range.includes(Phase::Build));
+        assert!(!range.includes(Phase::Version));
+    }
+
+    #[test]
+    fn test_phase_range_rejects_backwards_order() {
+        assert!(PhaseRange::new(Phase::Artifacts, Phase::Deps).is_err());
+    }
+
+    #[test]
+    fn test_phase_range_single_phase() {
+        let range = PhaseRange::new(Phase::Build, Phase::Build).unwrap();
+        assert!(range.includes(Phase::Build));
+        assert!(!range.includes(Phase::Deps));
+        assert!(!range.includes(Phase::Artifacts));
+    }
+}
@@ -2,10 +2,13 @@
 //!
 //! Safely parses PKGBUILD files without executing shell code.
 
+use super::depends::Dependency;
+use super::version::compare_versions;
 use crate::error::{BuilderError, Result};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tracing::{debug, instrument};
 
 /// Information extracted from a PKGBUILD file
@@ -18,6 +21,12 @@ pub struct PkgbuildInfo {
     pub version: String,
     /// Package release number
     pub release: String,
+    /// Epoch, used to force a version to be considered newer than it would
+    /// otherwise sort (the `epoch=` PKGBUILD variable). Absent means `0`.
+    pub epoch: Option<String>,
+    /// Base name shared by every package a split PKGBUILD produces. Only
+    /// meaningful when `pkgname` is an array (see [`PkgbuildParser::parse_packages`]).
+    pub pkgbase: Option<String>,
     /// Supported architectures
     pub arch: Vec<String>,
     /// Runtime dependencies
@@ -26,6 +35,32 @@ pub struct PkgbuildInfo {
     pub make_depends: Vec<String>,
     /// Test dependencies
     pub check_depends: Vec<String>,
+    /// Optional dependencies, each as its raw `"name: description"` entry
+    pub optdepends: Vec<String>,
+    /// Virtual packages this package provides
+    pub provides: Vec<String>,
+    /// Packages this package conflicts with
+    pub conflicts: Vec<String>,
+    /// Packages this package replaces
+    pub replaces: Vec<String>,
+    /// Source file/URL entries (the `source` array)
+    pub source: Vec<String>,
+    /// Expected checksums for each `source` entry, in the same order
+    pub sha256sums: Vec<String>,
+    /// Expected SHA-1 checksums for each `source` entry, in the same order
+    pub sha1sums: Vec<String>,
+    /// Expected SHA-512 checksums for each `source` entry, in the same order
+    pub sha512sums: Vec<String>,
+    /// Expected BLAKE2b checksums for each `source` entry, in the same order
+    pub b2sums: Vec<String>,
+    /// Expected MD5 checksums for each `source` entry, in the same order
+    pub md5sums: Vec<String>,
+    /// Sibling PKGBUILD directories to build before this one (the
+    /// non-standard `_localdepends` array)
+    pub local_depends: Vec<PathBuf>,
+    /// System/C libraries whose presence is probed for rather than
+    /// installed (the non-standard `_nativedepends` array)
+    pub native_depends: Vec<String>,
 }
 
 impl PkgbuildInfo {
@@ -34,11 +69,22 @@ impl PkgbuildInfo {
         Self::default()
     }
     
-    /// Get the full package version (version-release)
+    /// Get the full package version, as `epoch:version-release` when an
+    /// epoch is present or plain `version-release` otherwise.
     pub fn full_version(&self) -> String {
-        format!("{}-{}", self.version, self.release)
+        match &self.epoch {
+            Some(epoch) => format!("{epoch}:{}-{}", self.version, self.release),
+            None => format!("{}-{}", self.version, self.release),
+        }
     }
-    
+
+    /// Whether this package is a newer version than `other`, using the same
+    /// `epoch`/`pkgver`/`pkgrel` ordering as makepkg's `vercmp`.
+    #[must_use]
+    pub fn is_newer_than(&self, other: &Self) -> bool {
+        compare_versions(&self.full_version(), &other.full_version()) == std::cmp::Ordering::Greater
+    }
+
     /// Get all dependencies combined
     pub fn all_dependencies(&self) -> Vec<String> {
         let mut deps = self.depends.clone();
@@ -49,7 +95,178 @@ impl PkgbuildInfo {
     
     /// Check if the package has any dependencies
     pub fn has_dependencies(&self) -> bool {
-        !self.depends.is_empty() || !self.make_depends.is_empty() || !self.check_depends.is_empty()
+        !self.depends.is_empty()
+            || !self.make_depends.is_empty()
+            || !self.check_depends.is_empty()
+            || !self.local_depends.is_empty()
+            || !self.native_depends.is_empty()
+    }
+
+    /// All dependencies as typed [`Dependency`] values: repo package names
+    /// from `depends`/`makedepends`/`checkdepends`, plus local-path and
+    /// native-library declarations from the `_localdepends`/`_nativedepends`
+    /// arrays.
+    pub fn typed_dependencies(&self) -> Vec<Dependency> {
+        let mut deps: Vec<Dependency> = self.all_dependencies().into_iter().map(Dependency::Repo).collect();
+        deps.extend(self.local_depends.iter().cloned().map(Dependency::LocalPath));
+        deps.extend(self.native_depends.iter().cloned().map(Dependency::Native));
+        deps
+    }
+
+    /// Parse `depends` entries (e.g. `"dep2>=1.0"`) into structured
+    /// [`DependencySpec`] values. The raw strings stay in `depends`
+    /// untouched, so round-tripping back into a PKGBUILD array doesn't lose
+    /// anything this parser doesn't understand.
+    pub fn parsed_depends(&self) -> Vec<DependencySpec> {
+        self.depends.iter().map(|d| DependencySpec::parse(d)).collect()
+    }
+
+    /// Validate `pkgname`/`pkgver`/`pkgrel` against makepkg's naming rules,
+    /// returning a distinct [`BuilderError`] variant for whichever is
+    /// violated first: `pkgver` may only contain letters, digits, periods,
+    /// and underscores (no hyphens, which separate version from release);
+    /// `pkgrel` must be a positive number, optionally with a decimal point;
+    /// `pkgname` must be lowercase, contain only alphanumerics plus
+    /// `@ . _ + -`, and not start with `-` or `.`.
+    pub fn validate(&self) -> Result<()> {
+        if !self.version.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_') {
+            return Err(BuilderError::invalid_pkgver(self.version.clone()));
+        }
+
+        if !is_valid_pkgrel(&self.release) {
+            return Err(BuilderError::invalid_pkgrel(self.release.clone()));
+        }
+
+        if self.name.chars().any(|c| c.is_ascii_uppercase()) {
+            return Err(BuilderError::invalid_pkgname(self.name.clone(), "must be lowercase"));
+        }
+        if self.name.starts_with('-') || self.name.starts_with('.') {
+            return Err(BuilderError::invalid_pkgname(
+                self.name.clone(),
+                "must not start with '-' or '.'",
+            ));
+        }
+        if !self
+            .name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '.' | '_' | '+' | '-'))
+        {
+            return Err(BuilderError::invalid_pkgname(
+                self.name.clone(),
+                "must contain only alphanumerics and '@ . _ + -'",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Render the parsed metadata as a pretty-printed JSON manifest, for CI
+    /// pipelines and other tooling to consume without re-parsing the
+    /// PKGBUILD themselves.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| BuilderError::config(format!("Failed to serialize PKGBUILD info to JSON: {e}")))
+    }
+
+    /// Parse a JSON manifest produced by [`Self::to_json`] back into a
+    /// `PkgbuildInfo`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| BuilderError::config(format!("Failed to parse PKGBUILD info from JSON: {e}")))
+    }
+
+    /// Render the parsed metadata as a TOML manifest.
+    pub fn to_toml(&self) -> Result<String> {
+        toml::to_string_pretty(self)
+            .map_err(|e| BuilderError::config(format!("Failed to serialize PKGBUILD info to TOML: {e}")))
+    }
+
+    /// Parse a TOML manifest produced by [`Self::to_toml`] back into a
+    /// `PkgbuildInfo`.
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml)
+            .map_err(|e| BuilderError::config(format!("Failed to parse PKGBUILD info from TOML: {e}")))
+    }
+}
+
+/// Check that `release` is a positive number, optionally with a decimal
+/// point (e.g. `"1"`, `"3"`, `"1.1"`), as makepkg requires for `pkgrel`.
+fn is_valid_pkgrel(release: &str) -> bool {
+    let mut parts = release.splitn(2, '.');
+    let whole = parts.next().unwrap_or("");
+    let fraction = parts.next();
+
+    let whole_is_positive = !whole.is_empty()
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && whole.parse::<u64>().is_ok_and(|n| n > 0);
+
+    let fraction_is_valid = match fraction {
+        Some(f) => !f.is_empty() && f.chars().all(|c| c.is_ascii_digit()),
+        None => true,
+    };
+
+    whole_is_positive && fraction_is_valid
+}
+
+/// Comparison operator in a version-constrained dependency string
+/// (`foo>=1.0`), in the same sense makepkg/pacman use them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionConstraint {
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `=`
+    Eq,
+    /// `>=`
+    Ge,
+    /// `>`
+    Gt,
+}
+
+/// A single dependency entry split into its package name and, if present,
+/// the version it's constrained to. Plain entries with no operator (the
+/// common case) just have `constraint`/`version` set to `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencySpec {
+    /// Package name, with any version constraint stripped off
+    pub name: String,
+    /// Comparison operator, if the entry had one
+    pub constraint: Option<VersionConstraint>,
+    /// Version the constraint applies to, if the entry had one
+    pub version: Option<String>,
+}
+
+impl DependencySpec {
+    /// Split a raw dependency entry like `"foo"`, `"foo>=1.0"`, or
+    /// `"foo=2.1-3"` on its comparison operator. Multi-character operators
+    /// (`>=`, `<=`) are checked before the single-character ones so `"foo<=1.0"`
+    /// doesn't get misparsed as `"foo<" "=1.0"`.
+    #[must_use]
+    pub fn parse(entry: &str) -> Self {
+        const OPERATORS: &[(&str, VersionConstraint)] = &[
+            (">=", VersionConstraint::Ge),
+            ("<=", VersionConstraint::Le),
+            ("=", VersionConstraint::Eq),
+            ("<", VersionConstraint::Lt),
+            (">", VersionConstraint::Gt),
+        ];
+
+        for (op, constraint) in OPERATORS {
+            if let Some(idx) = entry.find(op) {
+                return Self {
+                    name: entry[..idx].to_string(),
+                    constraint: Some(*constraint),
+                    version: Some(entry[idx + op.len()..].to_string()),
+                };
+            }
+        }
+
+        Self {
+            name: entry.to_string(),
+            constraint: None,
+            version: None,
+        }
     }
 }
 
@@ -68,6 +285,10 @@ pub struct PkgbuildParser {
     re_comment: Regex,
     /// Regex for simple fallback parsing
     re_simple: Regex,
+    /// Regex for `$var` and `${var}` references
+    re_var_ref: Regex,
+    /// Regex for per-package `package_<name>() { ... }` function bodies
+    re_package_fn: Regex,
 }
 
 impl PkgbuildParser {
@@ -86,6 +307,10 @@ impl PkgbuildParser {
                 .map_err(|e| BuilderError::config(format!("Failed to compile regex: {}", e)))?,
             re_simple: Regex::new(r#"(?m)^([a-zA-Z_][a-zA-Z0-9_]*)\s*=\s*(.*)$"#)
                 .map_err(|e| BuilderError::config(format!("Failed to compile regex: {}", e)))?,
+            re_var_ref: Regex::new(r#"\$\{([a-zA-Z_][a-zA-Z0-9_]*)\}|\$([a-zA-Z_][a-zA-Z0-9_]*)"#)
+                .map_err(|e| BuilderError::config(format!("Failed to compile regex: {}", e)))?,
+            re_package_fn: Regex::new(r#"(?ms)^\s*package_([a-zA-Z0-9_.+-]+)\s*\(\)\s*\{(.*?)^\}"#)
+                .map_err(|e| BuilderError::config(format!("Failed to compile regex: {}", e)))?,
         })
     }
 
@@ -98,6 +323,10 @@ impl PkgbuildParser {
         let content = std::fs::read_to_string(path)
             .map_err(|e| BuilderError::file_system("read", path.to_path_buf(), e))?;
 
+        // Expand `$var`/`${var}` references (e.g. `pkgver=${_basever}.3`)
+        // against the file's own assignments before extracting any fields.
+        let content = self.expand_variables(&content);
+
         let mut info = PkgbuildInfo::new();
 
         // Log first few lines for debugging
@@ -128,6 +357,93 @@ impl PkgbuildParser {
         Ok(info)
     }
 
+    /// Parse a PKGBUILD that may declare multiple output packages via a
+    /// split `pkgname=(...)` array, one per `package_<name>() { ... }`
+    /// function. Shared fields (version, release, epoch, ...) come from the
+    /// top-level assignments; each package's function body is then parsed
+    /// the same way and overrides whichever array fields it redeclares; a
+    /// scalar field (`pkgver`, `pkgrel`, `epoch`, `pkgbase`) already set at
+    /// the top level keeps its top-level value. A
+    /// PKGBUILD with a plain single-string `pkgname` isn't a split package
+    /// and comes back as a one-element vec with no overrides applied.
+    #[instrument(skip(self))]
+    pub fn parse_packages<P: AsRef<Path> + std::fmt::Debug>(&self, path: P) -> Result<Vec<PkgbuildInfo>> {
+        let path = path.as_ref();
+        debug!("Parsing PKGBUILD file (split-package aware): {}", path.display());
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| BuilderError::file_system("read", path.to_path_buf(), e))?;
+        let content = self.expand_variables(&content);
+
+        // Parse base/shared fields from the preamble only, so a variable
+        // declared inside one `package_<name>()` function (e.g. an
+        // `optdepends` that only one sub-package has) doesn't leak into
+        // `base` and from there into every sibling package.
+        let base_content = self.base_content(&content);
+        let mut base = PkgbuildInfo::new();
+        self.parse_single_variables(base_content, &mut base)?;
+        self.parse_array_variables(base_content, &mut base)?;
+
+        let Some(names) = self.split_package_names(&content).filter(|names| names.len() > 1) else {
+            if base.name.is_empty() || base.version.is_empty() || base.release.is_empty() {
+                self.fallback_parse(&content, &mut base)?;
+            }
+            self.validate_info(&base, path)?;
+            return Ok(vec![base]);
+        };
+
+        debug!("Found split package names: {:?}", names);
+        let functions = self.parse_package_functions(&content);
+
+        let mut packages = Vec::with_capacity(names.len());
+        for name in names {
+            let mut info = base.clone();
+            info.name = name.clone();
+            if let Some(body) = functions.get(&name) {
+                self.parse_single_variables(body, &mut info)?;
+                self.parse_array_variables(body, &mut info)?;
+            }
+            self.validate_info(&info, path)?;
+            packages.push(info);
+        }
+
+        Ok(packages)
+    }
+
+    /// The portion of `content` before the first `package_<name>() { ... }`
+    /// function, i.e. just the shared top-level assignments. Parsing only
+    /// this slice for base fields keeps per-package overrides from leaking
+    /// into every sibling package that doesn't redeclare the same key.
+    fn base_content<'a>(&self, content: &'a str) -> &'a str {
+        self.re_package_fn
+            .find(content)
+            .map_or(content, |m| &content[..m.start()])
+    }
+
+    /// If `pkgname` is declared as an array (`pkgname=('a' 'b')`) rather
+    /// than a plain string, return its entries.
+    fn split_package_names(&self, content: &str) -> Option<Vec<String>> {
+        self.re_array.captures_iter(content).find_map(|cap| {
+            let key = cap.get(1)?.as_str().trim();
+            if key != "pkgname" {
+                return None;
+            }
+            self.clean_array_content(cap.get(2)?.as_str()).ok()
+        })
+    }
+
+    /// Collect every `package_<name>() { ... }` function body, keyed by
+    /// package name, so each can be parsed as its own set of overrides.
+    fn parse_package_functions(&self, content: &str) -> HashMap<String, String> {
+        let mut functions = HashMap::new();
+        for cap in self.re_package_fn.captures_iter(content) {
+            if let (Some(name), Some(body)) = (cap.get(1), cap.get(2)) {
+                functions.insert(name.as_str().to_string(), body.as_str().to_string());
+            }
+        }
+        functions
+    }
+
     /// Parse single-value variables (pkgname, pkgver, pkgrel)
     fn parse_single_variables(&self, content: &str, info: &mut PkgbuildInfo) -> Result<()> {
         // Process different quote types
@@ -155,6 +471,18 @@ impl PkgbuildParser {
                     "depends" => info.depends = cleaned_array,
                     "makedepends" => info.make_depends = cleaned_array,
                     "checkdepends" => info.check_depends = cleaned_array,
+                    "optdepends" => info.optdepends = cleaned_array,
+                    "provides" => info.provides = cleaned_array,
+                    "conflicts" => info.conflicts = cleaned_array,
+                    "replaces" => info.replaces = cleaned_array,
+                    "source" => info.source = cleaned_array,
+                    "sha256sums" => info.sha256sums = cleaned_array,
+                    "sha1sums" => info.sha1sums = cleaned_array,
+                    "sha512sums" => info.sha512sums = cleaned_array,
+                    "b2sums" => info.b2sums = cleaned_array,
+                    "md5sums" => info.md5sums = cleaned_array,
+                    "_localdepends" => info.local_depends = cleaned_array.into_iter().map(PathBuf::from).collect(),
+                    "_nativedepends" => info.native_depends = cleaned_array,
                     _ => {}
                 }
             }
@@ -184,6 +512,8 @@ impl PkgbuildParser {
                     "pkgname" if info.name.is_empty() => info.name = val.to_string(),
                     "pkgver" if info.version.is_empty() => info.version = val.to_string(),
                     "pkgrel" if info.release.is_empty() => info.release = val.to_string(),
+                    "epoch" if info.epoch.is_none() => info.epoch = Some(val.to_string()),
+                    "pkgbase" if info.pkgbase.is_none() => info.pkgbase = Some(val.to_string()),
                     _ => {}
                 }
             }
@@ -191,6 +521,56 @@ impl PkgbuildParser {
         Ok(())
     }
 
+    /// Resolve `$var`/`${var}` references against a map of every
+    /// `name=value` assignment in the file (any quote style, including
+    /// leading-underscore custom vars), so helper variables expand before
+    /// field extraction. A variable's own value can itself reference one
+    /// defined elsewhere in the file regardless of order, so this runs the
+    /// substitution twice: the first pass resolves most references and
+    /// picks up any assignment whose right-hand side contained one, and the
+    /// second pass resolves references to *those* now-expanded values.
+    /// Anything still unresolved after that is left empty rather than
+    /// leaking literal `$var` text into parsed fields.
+    fn expand_variables(&self, content: &str) -> String {
+        let first_pass = self.substitute_variables(content, &self.collect_variable_map(content));
+        let second_pass_map = self.collect_variable_map(&first_pass);
+        let second_pass = self.substitute_variables(&first_pass, &second_pass_map);
+        // A chain deeper than two passes can follow leaves references that
+        // the second pass itself inserted; blank those out as unresolved.
+        self.substitute_variables(&second_pass, &HashMap::new())
+    }
+
+    /// Collect every `name=value` assignment (any quote style) into a
+    /// substitution map. Later assignments overwrite earlier ones, matching
+    /// the sequential-execution semantics a shell would have.
+    fn collect_variable_map(&self, content: &str) -> HashMap<String, String> {
+        let mut assignments = Vec::new();
+        for regex in [&self.re_double_quoted, &self.re_single_quoted, &self.re_unquoted] {
+            for cap in regex.captures_iter(content) {
+                if let (Some(key), Some(val)) = (cap.get(1), cap.get(2)) {
+                    assignments.push((key.start(), key.as_str().trim().to_string(), val.as_str().trim().to_string()));
+                }
+            }
+        }
+        // Insert in file order rather than regex order, so the later
+        // assignment wins whatever its quote style.
+        assignments.sort_by_key(|(pos, _, _)| *pos);
+        let vars: HashMap<String, String> = assignments.into_iter().map(|(_, key, val)| (key, val)).collect();
+        debug!("Collected {} variables for substitution", vars.len());
+        vars
+    }
+
+    /// Replace every `$var`/`${var}` reference in `content` using `vars`,
+    /// leaving unresolved references as an empty string.
+    fn substitute_variables(&self, content: &str, vars: &HashMap<String, String>) -> String {
+        self.re_var_ref
+            .replace_all(content, |caps: &regex::Captures| {
+                let name = caps.get(1).or_else(|| caps.get(2)).map(|m| m.as_str()).unwrap_or_default();
+                vars.get(name).cloned().unwrap_or_default()
+            })
+            .into_owned()
+    }
+
     /// Clean array content by removing comments, quotes, and normalizing whitespace
     fn clean_array_content(&self, content: &str) -> Result<Vec<String>> {
         // Remove comments
@@ -232,6 +612,8 @@ impl PkgbuildParser {
                     "pkgname" if info.name.is_empty() => info.name = val.to_string(),
                     "pkgver" if info.version.is_empty() => info.version = val.to_string(),
                     "pkgrel" if info.release.is_empty() => info.release = val.to_string(),
+                    "epoch" if info.epoch.is_none() => info.epoch = Some(val.to_string()),
+                    "pkgbase" if info.pkgbase.is_none() => info.pkgbase = Some(val.to_string()),
                     _ => {}
                 }
             }
@@ -251,6 +633,9 @@ impl PkgbuildParser {
                 path,
             ));
         }
+
+        info.validate()?;
+
         Ok(())
     }
 }
@@ -318,6 +703,134 @@ makedepends=('build-dep1' 'build-dep2')
         assert_eq!(info.make_depends, vec!["build-dep1", "build-dep2"]);
     }
 
+    #[test]
+    fn test_parse_source_and_checksums() {
+        let content = r#"
+pkgname=test-package
+pkgver=1.0.0
+pkgrel=1
+source=('https://example.com/test-1.0.0.tar.gz')
+sha256sums=('c2b1b1b2e9f5b3b5c2b1b1b2e9f5b3b5c2b1b1b2e9f5b3b5c2b1b1b2e9f5b3b5')
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let info = parser.parse(file.path()).unwrap();
+
+        assert_eq!(info.source, vec!["https://example.com/test-1.0.0.tar.gz"]);
+        assert_eq!(
+            info.sha256sums,
+            vec!["c2b1b1b2e9f5b3b5c2b1b1b2e9f5b3b5c2b1b1b2e9f5b3b5c2b1b1b2e9f5b3b5"]
+        );
+    }
+
+    #[test]
+    fn test_parse_expands_braced_and_bare_variable_references() {
+        let content = r#"
+_basever=1.2
+pkgname=test-package
+pkgver=${_basever}.3
+pkgrel=1
+source=("$pkgname-$pkgver.tar.gz")
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let info = parser.parse(file.path()).unwrap();
+
+        assert_eq!(info.version, "1.2.3");
+        assert_eq!(info.source, vec!["test-package-1.2.3.tar.gz"]);
+    }
+
+    #[test]
+    fn test_parse_expands_forward_referenced_variable_chain() {
+        // _pkgver2 is defined (and used) before _basever, the variable it
+        // itself references; the second substitution pass should still
+        // resolve it.
+        let content = r#"
+pkgname=test-package
+pkgver=${_pkgver2}
+pkgrel=1
+_pkgver2=${_basever}.3
+_basever=1.2
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let info = parser.parse(file.path()).unwrap();
+
+        assert_eq!(info.version, "1.2.3");
+    }
+
+    #[test]
+    fn test_parse_leaves_unresolved_variable_reference_empty() {
+        let content = r#"
+pkgname=test-package
+pkgver=${_undefined}
+pkgrel=1
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let result = parser.parse(file.path());
+
+        // pkgver resolves to an empty string, which the fallback parser
+        // can't recover either, so this is a parse error rather than a
+        // silently wrong version.
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_accepts_well_formed_info() {
+        let info = PkgbuildInfo {
+            name: "test-package".to_string(),
+            version: "1.2.3_beta".to_string(),
+            release: "1.1".to_string(),
+            ..Default::default()
+        };
+        assert!(info.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_hyphen_in_pkgver() {
+        let info = PkgbuildInfo {
+            name: "test".to_string(),
+            version: "1.0-rc1".to_string(),
+            release: "1".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(info.validate(), Err(BuilderError::InvalidPkgver { .. })));
+    }
+
+    #[test]
+    fn test_validate_rejects_non_positive_pkgrel() {
+        let info = PkgbuildInfo {
+            name: "test".to_string(),
+            version: "1.0.0".to_string(),
+            release: "0".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(info.validate(), Err(BuilderError::InvalidPkgrel { .. })));
+    }
+
+    #[test]
+    fn test_validate_rejects_uppercase_pkgname() {
+        let info = PkgbuildInfo {
+            name: "Test-Package".to_string(),
+            version: "1.0.0".to_string(),
+            release: "1".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(info.validate(), Err(BuilderError::InvalidPkgname { .. })));
+    }
+
+    #[test]
+    fn test_validate_rejects_pkgname_starting_with_dot() {
+        let info = PkgbuildInfo {
+            name: ".hidden".to_string(),
+            version: "1.0.0".to_string(),
+            release: "1".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(info.validate(), Err(BuilderError::InvalidPkgname { .. })));
+    }
+
     #[test]
     fn test_full_version() {
         let info = PkgbuildInfo {
@@ -343,4 +856,225 @@ makedepends=('build-dep1' 'build-dep2')
         assert!(all_deps.contains(&"makedep1".to_string()));
         assert!(all_deps.contains(&"checkdep1".to_string()));
     }
+
+    #[test]
+    fn test_parse_local_and_native_depends() {
+        let content = r#"
+pkgname=test-package
+pkgver=1.0.0
+pkgrel=1
+depends=('glibc')
+_localdepends=('../libfoo' '../libbar')
+_nativedepends=('libssl' 'zlib')
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let info = parser.parse(file.path()).unwrap();
+
+        assert_eq!(info.local_depends, vec![PathBuf::from("../libfoo"), PathBuf::from("../libbar")]);
+        assert_eq!(info.native_depends, vec!["libssl", "zlib"]);
+    }
+
+    #[test]
+    fn test_typed_dependencies() {
+        let info = PkgbuildInfo {
+            depends: vec!["dep1".to_string()],
+            local_depends: vec![PathBuf::from("../libfoo")],
+            native_depends: vec!["libssl".to_string()],
+            ..Default::default()
+        };
+
+        let typed = info.typed_dependencies();
+        assert_eq!(typed.len(), 3);
+        assert!(typed.contains(&Dependency::Repo("dep1".to_string())));
+        assert!(typed.contains(&Dependency::LocalPath(PathBuf::from("../libfoo"))));
+        assert!(typed.contains(&Dependency::Native("libssl".to_string())));
+    }
+
+    #[test]
+    fn test_dependency_spec_parse_plain_name() {
+        let spec = DependencySpec::parse("glibc");
+        assert_eq!(spec.name, "glibc");
+        assert_eq!(spec.constraint, None);
+        assert_eq!(spec.version, None);
+    }
+
+    #[test]
+    fn test_dependency_spec_parse_operators() {
+        let cases = [
+            (">=1.0", VersionConstraint::Ge),
+            ("<=1.0", VersionConstraint::Le),
+            ("=1.0", VersionConstraint::Eq),
+            ("<1.0", VersionConstraint::Lt),
+            (">1.0", VersionConstraint::Gt),
+        ];
+
+        for (op, constraint) in cases {
+            let spec = DependencySpec::parse(&format!("dep{op}"));
+            assert_eq!(spec.name, "dep");
+            assert_eq!(spec.constraint, Some(constraint));
+            assert_eq!(spec.version.as_deref(), Some("1.0"));
+        }
+    }
+
+    #[test]
+    fn test_parsed_depends() {
+        let info = PkgbuildInfo {
+            depends: vec!["glibc".to_string(), "dep2>=1.0".to_string()],
+            ..Default::default()
+        };
+
+        let parsed = info.parsed_depends();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], DependencySpec::parse("glibc"));
+        assert_eq!(parsed[1], DependencySpec::parse("dep2>=1.0"));
+        // Raw strings are kept untouched for round-tripping
+        assert_eq!(info.depends, vec!["glibc", "dep2>=1.0"]);
+    }
+
+    #[test]
+    fn test_is_newer_than_compares_version_and_release() {
+        let older = PkgbuildInfo {
+            version: "1.0.0".to_string(),
+            release: "1".to_string(),
+            ..Default::default()
+        };
+        let newer = PkgbuildInfo {
+            version: "1.0.1".to_string(),
+            release: "1".to_string(),
+            ..Default::default()
+        };
+        assert!(newer.is_newer_than(&older));
+        assert!(!older.is_newer_than(&newer));
+    }
+
+    #[test]
+    fn test_is_newer_than_epoch_overrides_version() {
+        let low_epoch = PkgbuildInfo {
+            version: "9.0.0".to_string(),
+            release: "1".to_string(),
+            ..Default::default()
+        };
+        let high_epoch = PkgbuildInfo {
+            epoch: Some("1".to_string()),
+            version: "1.0.0".to_string(),
+            release: "1".to_string(),
+            ..Default::default()
+        };
+        assert!(high_epoch.is_newer_than(&low_epoch));
+    }
+
+    #[test]
+    fn test_parse_extended_metadata_fields() {
+        let content = r#"
+pkgname=test-package
+pkgver=1.0.0
+pkgrel=1
+epoch=2
+pkgbase=test-base
+provides=('libtest.so')
+conflicts=('test-package-old')
+replaces=('test-package-legacy')
+optdepends=('test-extra: extra features')
+source=('test-1.0.0.tar.gz')
+sha256sums=('abc123')
+b2sums=('def456')
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let info = parser.parse(file.path()).unwrap();
+
+        assert_eq!(info.epoch.as_deref(), Some("2"));
+        assert_eq!(info.pkgbase.as_deref(), Some("test-base"));
+        assert_eq!(info.provides, vec!["libtest.so"]);
+        assert_eq!(info.conflicts, vec!["test-package-old"]);
+        assert_eq!(info.replaces, vec!["test-package-legacy"]);
+        assert_eq!(info.optdepends, vec!["test-extra: extra features"]);
+        assert_eq!(info.b2sums, vec!["def456"]);
+        assert_eq!(info.full_version(), "2:1.0.0-1");
+    }
+
+    #[test]
+    fn test_parse_packages_single_package_returns_one_element() {
+        let content = r#"
+pkgname=test-package
+pkgver=1.0.0
+pkgrel=1
+depends=('glibc')
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let packages = parser.parse_packages(file.path()).unwrap();
+
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "test-package");
+    }
+
+    #[test]
+    fn test_parse_packages_split_package_applies_overrides() {
+        let content = r#"
+pkgbase=test-suite
+pkgname=('test-core' 'test-extra')
+pkgver=1.0.0
+pkgrel=1
+depends=('glibc')
+
+package_test-core() {
+    depends=('glibc' 'test-common')
+}
+
+package_test-extra() {
+    depends=('test-core')
+    optdepends=('test-plugin: adds plugin support')
+}
+"#;
+        let file = create_test_pkgbuild(content);
+        let parser = PkgbuildParser::new().unwrap();
+        let packages = parser.parse_packages(file.path()).unwrap();
+
+        assert_eq!(packages.len(), 2);
+
+        let core = packages.iter().find(|p| p.name == "test-core").unwrap();
+        assert_eq!(core.version, "1.0.0");
+        assert_eq!(core.release, "1");
+        assert_eq!(core.depends, vec!["glibc", "test-common"]);
+        // `optdepends` is only declared inside `package_test-extra()` and
+        // must not leak into a sibling package that never redeclares it.
+        assert!(core.optdepends.is_empty());
+
+        let extra = packages.iter().find(|p| p.name == "test-extra").unwrap();
+        assert_eq!(extra.depends, vec!["test-core"]);
+        assert_eq!(extra.optdepends, vec!["test-plugin: adds plugin support"]);
+        assert_eq!(extra.pkgbase.as_deref(), Some("test-suite"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let info = PkgbuildInfo {
+            name: "test-package".to_string(),
+            version: "1.0.0".to_string(),
+            release: "1".to_string(),
+            depends: vec!["glibc".to_string()],
+            ..Default::default()
+        };
+
+        let json = info.to_json().unwrap();
+        let parsed = PkgbuildInfo::from_json(&json).unwrap();
+        assert_eq!(parsed, info);
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let info = PkgbuildInfo {
+            name: "test-package".to_string(),
+            version: "1.0.0".to_string(),
+            release: "1".to_string(),
+            provides: vec!["libtest.so".to_string()],
+            ..Default::default()
+        };
+
+        let toml = info.to_toml().unwrap();
+        let parsed = PkgbuildInfo::from_toml(&toml).unwrap();
+        assert_eq!(parsed, info);
+    }
 }
\ No newline at end of file
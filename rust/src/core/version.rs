@@ -0,0 +1,212 @@
+//! makepkg-compatible version comparison
+//!
+//! Implements the same ordering as `vercmp`/libalpm: epochs compare
+//! numerically first, then the `version` part is split into alternating
+//! runs of digits and non-digits and compared component by component, and
+//! ties are finally broken by `pkgrel`.
+
+use std::cmp::Ordering;
+
+/// Compare two full `epoch:version-release` strings (either part may be
+/// absent: no `:` means epoch `0`, no trailing `-release` skips the
+/// `pkgrel` tie-break).
+#[must_use]
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (epoch_a, rest_a) = split_epoch(a);
+    let (epoch_b, rest_b) = split_epoch(b);
+
+    match epoch_a.cmp(&epoch_b) {
+        Ordering::Equal => {}
+        other => return other,
+    }
+
+    let (version_a, release_a) = split_release(rest_a);
+    let (version_b, release_b) = split_release(rest_b);
+
+    match compare_version_strings(version_a, version_b) {
+        Ordering::Equal => {}
+        other => return other,
+    }
+
+    compare_pkgrel(release_a, release_b)
+}
+
+/// Split off a leading `epoch:` prefix, defaulting to epoch `0` if absent
+/// or unparsable.
+fn split_epoch(s: &str) -> (u64, &str) {
+    match s.split_once(':') {
+        Some((epoch, rest)) => (epoch.parse().unwrap_or(0), rest),
+        None => (0, s),
+    }
+}
+
+/// Split off a trailing `-release` suffix. `pkgver` can never contain a
+/// hyphen (enforced by [`crate::core::pkgbuild::PkgbuildInfo::validate`]),
+/// so the last `-` in the string is unambiguously the version/release
+/// separator.
+fn split_release(s: &str) -> (&str, Option<&str>) {
+    match s.rsplit_once('-') {
+        Some((version, release)) => (version, Some(release)),
+        None => (s, None),
+    }
+}
+
+/// `pkgrel` ties are broken the same way version components are: it's
+/// itself a dot-separated run of numbers (e.g. `"1.1"`).
+fn compare_pkgrel(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => compare_version_strings(a, b),
+        _ => Ordering::Equal,
+    }
+}
+
+/// A single alternating digit/non-digit run from [`split_components`].
+#[derive(Debug, PartialEq, Eq)]
+enum Component<'a> {
+    Numeric(&'a str),
+    Alpha(&'a str),
+}
+
+/// Split a version string into alternating runs of digits and non-digits.
+/// `.`, `_`, and `+` are component boundaries only — they're never included
+/// in a component and never compared themselves.
+fn split_components(s: &str) -> Vec<Component<'_>> {
+    let bytes = s.as_bytes();
+    let mut components = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if matches!(c, '.' | '_' | '+') {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let is_digit_run = c.is_ascii_digit();
+        while i < bytes.len() {
+            let c = bytes[i] as char;
+            if matches!(c, '.' | '_' | '+') || c.is_ascii_digit() != is_digit_run {
+                break;
+            }
+            i += 1;
+        }
+
+        let slice = &s[start..i];
+        components.push(if is_digit_run {
+            Component::Numeric(slice)
+        } else {
+            Component::Alpha(slice)
+        });
+    }
+
+    components
+}
+
+/// Compare two version strings component by component. A purely numeric
+/// component always outranks an alphabetic one at the same position. When
+/// one side runs out of components, the longer one is newer unless its
+/// first extra component is a recognized pre-release keyword, which sorts
+/// *lower* than the absent component (so `"1.0"` > `"1.0rc1"`).
+fn compare_version_strings(a: &str, b: &str) -> Ordering {
+    let comps_a = split_components(a);
+    let comps_b = split_components(b);
+
+    let shared = comps_a.len().min(comps_b.len());
+    for (comp_a, comp_b) in comps_a.iter().zip(comps_b.iter()).take(shared) {
+        match compare_component(comp_a, comp_b) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+
+    match comps_a.len().cmp(&comps_b.len()) {
+        Ordering::Equal => Ordering::Equal,
+        Ordering::Greater if is_prerelease_keyword(&comps_a[shared]) => Ordering::Less,
+        Ordering::Greater => Ordering::Greater,
+        Ordering::Less if is_prerelease_keyword(&comps_b[shared]) => Ordering::Greater,
+        Ordering::Less => Ordering::Less,
+    }
+}
+
+fn compare_component(a: &Component, b: &Component) -> Ordering {
+    match (a, b) {
+        (Component::Numeric(a), Component::Numeric(b)) => compare_numeric(a, b),
+        (Component::Alpha(a), Component::Alpha(b)) => a.cmp(b),
+        (Component::Numeric(_), Component::Alpha(_)) => Ordering::Greater,
+        (Component::Alpha(_), Component::Numeric(_)) => Ordering::Less,
+    }
+}
+
+/// Compare two digit runs numerically without risking integer overflow on
+/// arbitrarily long version numbers: strip leading zeros, then compare by
+/// length before falling back to a lexicographic compare of equal-length runs.
+fn compare_numeric(a: &str, b: &str) -> Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    match a.len().cmp(&b.len()) {
+        Ordering::Equal => a.cmp(b),
+        other => other,
+    }
+}
+
+/// Recognized pre-release keywords that sort lower than their absence
+/// (`"1.0"` is newer than `"1.0alpha"`), case-insensitively.
+fn is_prerelease_keyword(component: &Component) -> bool {
+    matches!(component, Component::Alpha(s) if matches!(
+        s.to_ascii_lowercase().as_str(),
+        "alpha" | "beta" | "rc" | "pre"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions_equal() {
+        assert_eq!(compare_versions("1.0.0-1", "1.0.0-1"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_versions_by_numeric_component() {
+        assert_eq!(compare_versions("1.2.0-1", "1.10.0-1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_compare_versions_epoch_dominates() {
+        assert_eq!(compare_versions("1:1.0.0-1", "2.0.0-1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_compare_versions_pkgrel_tiebreak() {
+        assert_eq!(compare_versions("1.0.0-1", "1.0.0-2"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_compare_versions_longer_is_newer() {
+        assert_eq!(compare_versions("1.0.1-1", "1.0-1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_compare_versions_prerelease_keyword_sorts_lower() {
+        assert_eq!(compare_versions("1.0rc1-1", "1.0-1"), Ordering::Less);
+        assert_eq!(compare_versions("1.0alpha-1", "1.0-1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_compare_versions_numeric_outranks_alphabetic() {
+        assert_eq!(compare_versions("1.2-1", "1.a-1"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_compare_versions_ignores_separators() {
+        assert_eq!(compare_versions("1.2.3-1", "1_2_3-1"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_compare_numeric_strips_leading_zeros() {
+        assert_eq!(compare_numeric("007", "7"), Ordering::Equal);
+        assert_eq!(compare_numeric("10", "9"), Ordering::Greater);
+    }
+}
@@ -0,0 +1,249 @@
+//! Build freshness cache ("workcache")
+//!
+//! Records, for each successfully built package, a freshness signature of
+//! every tracked input (the PKGBUILD plus any local `source=()` files) and
+//! every output it produced, in a small JSON database under the artifacts
+//! output directory, keyed by package name.
+//! [`PackageBuilder::build`](crate::core::builder::PackageBuilder::build)
+//! consults it before invoking makepkg: if every input's signature still
+//! matches and every recorded output still exists on disk with a matching
+//! signature, the build is skipped entirely. The file is plain JSON, so
+//! it's safe to commit to the repo or cache between CI runs.
+
+use crate::error::{BuilderError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tracing::{debug, warn};
+
+/// Name of the workcache database file, stored under the artifacts output dir.
+pub const WORKCACHE_FILE: &str = ".pkgmanager-workcache.json";
+
+/// A freshness signature for a single file: a content hash where the file
+/// could be read, falling back to size+mtime (e.g. on a permission error)
+/// so a signature can still be computed instead of forcing a rebuild.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FileSignature {
+    /// SHA-256 of the file's contents, as a hex string
+    Hash(String),
+    /// Size in bytes and modification time (seconds since the Unix epoch)
+    SizeMtime { size: u64, mtime_secs: u64 },
+}
+
+impl FileSignature {
+    /// Compute a signature for the file at `path`. Returns `None` if the
+    /// file doesn't exist or its metadata can't be read at all.
+    #[must_use]
+    pub fn compute(path: &Path) -> Option<Self> {
+        if let Ok(bytes) = std::fs::read(path) {
+            let digest = Sha256::digest(&bytes);
+            let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+            return Some(Self::Hash(hex));
+        }
+
+        let metadata = std::fs::metadata(path).ok()?;
+        let mtime_secs = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Some(Self::SizeMtime {
+            size: metadata.len(),
+            mtime_secs,
+        })
+    }
+}
+
+/// One package's recorded build state: the signatures of everything that
+/// went into it and everything it produced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WorkCacheEntry {
+    /// Signature of each tracked input (PKGBUILD + local `source=()` files), by path
+    pub inputs: HashMap<PathBuf, FileSignature>,
+    /// Signature of each output this build produced, by path
+    pub outputs: HashMap<PathBuf, FileSignature>,
+}
+
+/// JSON database of per-package build fingerprints
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkCache {
+    packages: HashMap<String, WorkCacheEntry>,
+}
+
+impl WorkCache {
+    /// Load the workcache from `path`, returning an empty cache if it
+    /// doesn't exist yet or is unreadable/corrupt.
+    pub fn load(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Workcache at {} is corrupt, starting fresh: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(e) => {
+                warn!("Failed to read workcache at {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Write the workcache to `path`, creating parent directories as needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| BuilderError::file_system("create_dir_all", parent.to_path_buf(), e))?;
+        }
+
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| BuilderError::config(format!("Failed to serialize workcache: {e}")))?;
+
+        std::fs::write(path, json).map_err(|e| BuilderError::file_system("write", path.to_path_buf(), e))
+    }
+
+    /// Compute a signature map for every path in `paths` that currently
+    /// exists on disk; missing paths (e.g. a remote `source=()` URL that
+    /// was never downloaded locally) are silently skipped.
+    #[must_use]
+    pub fn signatures(paths: &[PathBuf]) -> HashMap<PathBuf, FileSignature> {
+        paths
+            .iter()
+            .filter_map(|path| FileSignature::compute(path).map(|sig| (path.clone(), sig)))
+            .collect()
+    }
+
+    /// Check whether `name` is fresh against the current `inputs`: the
+    /// recorded input signatures must match exactly, and every recorded
+    /// output must still exist on disk with an unchanged signature.
+    /// Returns the cached output paths if so.
+    #[must_use]
+    pub fn is_fresh(&self, name: &str, inputs: &HashMap<PathBuf, FileSignature>) -> Option<Vec<PathBuf>> {
+        let entry = self.packages.get(name)?;
+
+        if entry.inputs != *inputs {
+            return None;
+        }
+
+        if entry.outputs.is_empty() {
+            return None;
+        }
+
+        let outputs_unchanged = entry
+            .outputs
+            .iter()
+            .all(|(path, sig)| FileSignature::compute(path).as_ref() == Some(sig));
+
+        if !outputs_unchanged {
+            return None;
+        }
+
+        debug!("{} is fresh: {} input(s) unchanged", name, entry.inputs.len());
+        Some(entry.outputs.keys().cloned().collect())
+    }
+
+    /// Record a successful build, overwriting any previous entry for `name`
+    pub fn record(
+        &mut self,
+        name: impl Into<String>,
+        inputs: HashMap<PathBuf, FileSignature>,
+        outputs: HashMap<PathBuf, FileSignature>,
+    ) {
+        self.packages.insert(name.into(), WorkCacheEntry { inputs, outputs });
+    }
+
+    /// Drop any recorded entry for `name`, e.g. because `--clean` was
+    /// requested and any cached outputs it described may no longer exist.
+    pub fn invalidate(&mut self, name: &str) {
+        self.packages.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_missing_file_returns_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = WorkCache::load(&temp_dir.path().join("missing.json"));
+        assert!(cache.is_fresh("anything", &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_file_signature_changes_with_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("PKGBUILD");
+
+        std::fs::write(&path, "pkgver=1.0.0").unwrap();
+        let a = FileSignature::compute(&path).unwrap();
+
+        std::fs::write(&path, "pkgver=1.0.1").unwrap();
+        let b = FileSignature::compute(&path).unwrap();
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_is_fresh_requires_matching_inputs_and_existing_outputs() {
+        let temp_dir = TempDir::new().unwrap();
+        let pkgbuild_path = temp_dir.path().join("PKGBUILD");
+        std::fs::write(&pkgbuild_path, "pkgver=1.0.0").unwrap();
+        let pkg_file = temp_dir.path().join("test-1.0.0-1.pkg.tar.zst");
+        std::fs::write(&pkg_file, "data").unwrap();
+
+        let inputs = WorkCache::signatures(&[pkgbuild_path.clone()]);
+        let outputs = WorkCache::signatures(&[pkg_file.clone()]);
+
+        let mut cache = WorkCache::default();
+        cache.record("test-package", inputs.clone(), outputs);
+
+        assert_eq!(
+            cache.is_fresh("test-package", &inputs),
+            Some(vec![pkg_file.clone()])
+        );
+        assert!(cache.is_fresh("other-package", &inputs).is_none());
+
+        // Changing the input invalidates the cache
+        std::fs::write(&pkgbuild_path, "pkgver=1.0.1").unwrap();
+        let changed_inputs = WorkCache::signatures(&[pkgbuild_path.clone()]);
+        assert!(cache.is_fresh("test-package", &changed_inputs).is_none());
+
+        // Losing an output invalidates the cache even with matching inputs
+        std::fs::remove_file(&pkg_file).unwrap();
+        assert!(cache.is_fresh("test-package", &inputs).is_none());
+    }
+
+    #[test]
+    fn test_invalidate_removes_entry() {
+        let mut cache = WorkCache::default();
+        cache.record("test-package", HashMap::new(), HashMap::new());
+        cache.invalidate("test-package");
+        assert!(cache.is_fresh("test-package", &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("workcache.json");
+        let pkg_file = temp_dir.path().join("test.pkg.tar.zst");
+        std::fs::write(&pkg_file, "data").unwrap();
+
+        let mut cache = WorkCache::default();
+        let outputs = WorkCache::signatures(&[pkg_file.clone()]);
+        cache.record("test-package", HashMap::new(), outputs);
+        cache.save(&path).unwrap();
+
+        let loaded = WorkCache::load(&path);
+        assert_eq!(
+            loaded.is_fresh("test-package", &HashMap::new()),
+            Some(vec![pkg_file])
+        );
+    }
+}
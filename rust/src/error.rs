@@ -2,6 +2,7 @@
 //!
 //! Provides structured error handling with context and proper error chains.
 
+use serde::Serialize;
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -74,6 +75,27 @@ pub enum BuilderError {
     /// Validation errors
     #[error("Validation error: {message}")]
     Validation { message: String },
+
+    /// A process exceeded its configured timeout and was terminated
+    #[error("Process timed out after {timeout:?} and was terminated: {command}")]
+    Timeout {
+        command: String,
+        timeout: std::time::Duration,
+    },
+
+    /// `pkgver` contains characters makepkg forbids (only letters, digits,
+    /// `.`, and `_` are allowed — a hyphen would be ambiguous with the
+    /// version-release separator)
+    #[error("Invalid pkgver '{value}': must contain only letters, digits, periods, and underscores")]
+    InvalidPkgver { value: String },
+
+    /// `pkgrel` isn't a positive number (optionally with a decimal point)
+    #[error("Invalid pkgrel '{value}': must be a positive number, optionally with a decimal point")]
+    InvalidPkgrel { value: String },
+
+    /// `pkgname` violates makepkg's naming rules
+    #[error("Invalid pkgname '{value}': {reason}")]
+    InvalidPkgname { value: String, reason: String },
 }
 
 impl BuilderError {
@@ -164,6 +186,175 @@ impl BuilderError {
             message: message.into(),
         }
     }
+
+    /// Create a new timeout error
+    pub fn timeout(command: impl Into<String>, timeout: std::time::Duration) -> Self {
+        Self::Timeout {
+            command: command.into(),
+            timeout,
+        }
+    }
+
+    /// Create a new invalid-pkgver error
+    pub fn invalid_pkgver(value: impl Into<String>) -> Self {
+        Self::InvalidPkgver { value: value.into() }
+    }
+
+    /// Create a new invalid-pkgrel error
+    pub fn invalid_pkgrel(value: impl Into<String>) -> Self {
+        Self::InvalidPkgrel { value: value.into() }
+    }
+
+    /// Create a new invalid-pkgname error
+    pub fn invalid_pkgname(value: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidPkgname {
+            value: value.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Stable, machine-readable category for a [`BuilderError`] variant, letting
+/// the CLI branch on failure kind (e.g. to retry only `Process` failures)
+/// without matching the full error enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCategory {
+    /// PKGBUILD/metadata parsing failures
+    Parse,
+    /// Dependency resolution/installation failures
+    Dependency,
+    /// Package build failures
+    Build,
+    /// Artifact collection failures
+    Artifact,
+    /// File system operation failures
+    FileSystem,
+    /// External process failures, including timeouts
+    Process,
+    /// Configuration failures
+    Config,
+    /// Input validation failures
+    Validation,
+}
+
+impl ErrorCategory {
+    /// A stable, distinct process exit code for this category, so CI jobs
+    /// can branch on failure type without parsing the error message.
+    #[must_use]
+    pub const fn exit_code(self) -> i32 {
+        match self {
+            Self::Parse => 10,
+            Self::Dependency => 11,
+            Self::Build => 12,
+            Self::Artifact => 13,
+            Self::FileSystem => 14,
+            Self::Process => 15,
+            Self::Config => 16,
+            Self::Validation => 17,
+        }
+    }
+}
+
+/// Captured exit code/stdout/stderr of a failed process, included in a
+/// [`BuilderError::Process`]'s diagnostic.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessDiagnostic {
+    /// The command and arguments as they were invoked
+    pub command: String,
+    /// Exit status code, if the process ran to completion
+    pub exit_code: Option<i32>,
+    /// Captured standard output
+    pub stdout: String,
+    /// Captured standard error
+    pub stderr: String,
+}
+
+/// JSON-serializable diagnostic for a [`BuilderError`], produced by
+/// [`BuilderError::to_diagnostic_json`] so CI jobs can branch on failure type
+/// and recover failing-command output programmatically instead of scraping
+/// logs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorDiagnostic {
+    /// Stable failure category
+    pub category: ErrorCategory,
+    /// Process exit code this error maps to
+    pub exit_code: i32,
+    /// Human-readable error message
+    pub message: String,
+    /// File path the error relates to, if any
+    pub path: Option<PathBuf>,
+    /// Display of the underlying source error, if any
+    pub source: Option<String>,
+    /// Captured process output, for `Process` errors
+    pub process: Option<ProcessDiagnostic>,
+}
+
+impl BuilderError {
+    /// The stable category this error belongs to.
+    #[must_use]
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::PkgbuildParse { .. } => ErrorCategory::Parse,
+            Self::Dependency { .. } => ErrorCategory::Dependency,
+            Self::Build { .. } => ErrorCategory::Build,
+            Self::Artifact { .. } => ErrorCategory::Artifact,
+            Self::FileSystem { .. } => ErrorCategory::FileSystem,
+            Self::Process { .. } | Self::Timeout { .. } => ErrorCategory::Process,
+            Self::Config { .. } => ErrorCategory::Config,
+            Self::Validation { .. }
+            | Self::InvalidPkgver { .. }
+            | Self::InvalidPkgrel { .. }
+            | Self::InvalidPkgname { .. } => ErrorCategory::Validation,
+        }
+    }
+
+    /// A stable process exit code for this error, derived from its
+    /// [`category`](Self::category).
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        self.category().exit_code()
+    }
+
+    /// The file path this error relates to, if any.
+    fn path(&self) -> Option<PathBuf> {
+        match self {
+            Self::PkgbuildParse { path, .. } | Self::Artifact { path, .. } | Self::FileSystem { path, .. } => {
+                Some(path.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Captured process output, if this is a `Process` error.
+    fn process_diagnostic(&self) -> Option<ProcessDiagnostic> {
+        match self {
+            Self::Process { command, exit_code, stdout, stderr, .. } => Some(ProcessDiagnostic {
+                command: command.clone(),
+                exit_code: *exit_code,
+                stdout: stdout.clone(),
+                stderr: stderr.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Serialize this error's category, exit code, message, path, source
+    /// cause, and (for `Process` errors) captured command output as JSON, so
+    /// CI jobs can inspect a failure without scraping human-readable logs.
+    #[must_use]
+    pub fn to_diagnostic_json(&self) -> serde_json::Value {
+        let diagnostic = ErrorDiagnostic {
+            category: self.category(),
+            exit_code: self.exit_code(),
+            message: self.to_string(),
+            path: self.path(),
+            source: std::error::Error::source(self).map(|source| source.to_string()),
+            process: self.process_diagnostic(),
+        };
+
+        serde_json::to_value(&diagnostic).unwrap_or(serde_json::Value::Null)
+    }
 }
 
 /// Result type alias for convenience
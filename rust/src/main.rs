@@ -1,6 +1,6 @@
 #![allow(clippy::cargo_common_metadata)]
 use anyhow::Result;
-use pkgmanager_builder::{cli, config::Config, setup_logging};
+use pkgmanager_builder::{cli, config::Config, error::BuilderError, setup_logging};
 
 fn main() -> Result<()> {
     // Parse command line arguments
@@ -13,5 +13,19 @@ fn main() -> Result<()> {
     let config = Config::from_args(&args)?;
 
     // Execute the appropriate command
-    cli::execute_command(&config, &args.command)
+    let result = cli::execute_command(&config, &args.command);
+
+    if let Err(err) = &result {
+        // `execute_command` wraps the originating `BuilderError` in layers of
+        // `.context(...)`, so it isn't necessarily `err`'s own type — walk the
+        // whole source chain to find it.
+        if let Some(builder_error) = err.chain().find_map(|cause| cause.downcast_ref::<BuilderError>()) {
+            if config.json_events {
+                println!("{}", builder_error.to_diagnostic_json());
+            }
+            std::process::exit(builder_error.exit_code());
+        }
+    }
+
+    result
 }
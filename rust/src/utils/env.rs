@@ -3,7 +3,11 @@
 //! Provides functionality for generating version information and handling
 //! environment variables.
 
-use crate::{core::pkgbuild::PkgbuildInfo, error::Result, utils::fs::FileSystemUtils};
+use crate::{
+    core::pkgbuild::PkgbuildInfo,
+    error::{BuilderError, Result},
+    utils::{fs::FileSystemUtils, process::ProcessRunner},
+};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, env, path::Path};
@@ -13,6 +17,7 @@ use tracing::{debug, info, instrument};
 #[derive(Debug)]
 pub struct VersionGenerator {
     fs_utils: FileSystemUtils,
+    process_runner: ProcessRunner,
 }
 
 /// Complete version information structure
@@ -34,6 +39,293 @@ pub struct VersionInfo {
     pub build_date: String,
     /// Supported architectures
     pub arch: String,
+    /// `rustc --version` output of the compiler that built this binary
+    pub rustc_version: String,
+    /// Host target triple (e.g. `x86_64-unknown-linux-gnu`)
+    pub target_triple: String,
+    /// Build profile, `"debug"` or `"release"`
+    pub build_profile: String,
+    /// Git commit SHA (from the detected CI provider, falling back to
+    /// `git rev-parse HEAD`)
+    pub git_sha: String,
+    /// Whether the working tree had uncommitted changes (`git status --porcelain`)
+    pub git_dirty: bool,
+    /// Cargo features enabled for this build, best-effort from `CARGO_FEATURE_*`
+    /// environment variables (only populated when set by the invoking `cargo`
+    /// process; empty for a standalone compiled binary)
+    pub features: Vec<String>,
+}
+
+/// A semantic CI field a [`CiProvider`] may expose an environment variable
+/// name for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CiField {
+    /// The tag being built, if any (e.g. `v1.2.3`)
+    Tag,
+    /// The full commit SHA
+    CommitSha,
+    /// An abbreviated commit SHA
+    ShortSha,
+    /// The CI job identifier
+    JobId,
+    /// The CI pipeline/run identifier
+    PipelineId,
+    /// The container registry image reference
+    RegistryImage,
+}
+
+/// Normalized CI environment info, collected by [`CiProvider::collect`].
+/// `None` means the active provider doesn't expose that field (or no CI
+/// provider was detected).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CiInfo {
+    /// The tag being built, if any
+    pub tag: Option<String>,
+    /// The full commit SHA
+    pub commit_sha: Option<String>,
+    /// An abbreviated commit SHA
+    pub short_sha: Option<String>,
+    /// The CI job identifier
+    pub job_id: Option<String>,
+    /// The CI pipeline/run identifier
+    pub pipeline_id: Option<String>,
+    /// The container registry image reference
+    pub registry_image: Option<String>,
+}
+
+/// A CI environment that can detect itself from environment variables and
+/// map its native variables onto the semantic fields in [`CiInfo`].
+///
+/// Implementations know only how to *detect themselves* and *name their own
+/// variables*; [`collect`](Self::collect) is provided once, on top of
+/// [`var_name`](Self::var_name), so adding a new provider only means filling
+/// in those two methods.
+pub trait CiProvider {
+    /// Human-readable provider name, for logging/diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Whether this provider's CI environment is currently active
+    fn detect(&self) -> bool;
+
+    /// The canonical environment variable name this provider uses for
+    /// `field`, or `None` if this provider doesn't expose it.
+    fn var_name(&self, field: CiField) -> Option<&'static str>;
+
+    /// Read every field this provider knows a variable name for into a
+    /// normalized [`CiInfo`].
+    fn collect(&self) -> CiInfo {
+        let read = |field: CiField| self.var_name(field).and_then(|name| env::var(name).ok());
+
+        CiInfo {
+            tag: read(CiField::Tag),
+            commit_sha: read(CiField::CommitSha),
+            short_sha: read(CiField::ShortSha),
+            job_id: read(CiField::JobId),
+            pipeline_id: read(CiField::PipelineId),
+            registry_image: read(CiField::RegistryImage),
+        }
+    }
+}
+
+/// GitLab CI, detected via `GITLAB_CI`
+#[derive(Debug, Default)]
+pub struct GitLabCi;
+
+impl CiProvider for GitLabCi {
+    fn name(&self) -> &'static str {
+        "GitLab CI"
+    }
+
+    fn detect(&self) -> bool {
+        env::var("GITLAB_CI").is_ok()
+    }
+
+    fn var_name(&self, field: CiField) -> Option<&'static str> {
+        match field {
+            CiField::Tag => Some("CI_COMMIT_TAG"),
+            CiField::CommitSha => Some("CI_COMMIT_SHA"),
+            CiField::ShortSha => Some("CI_COMMIT_SHORT_SHA"),
+            CiField::JobId => Some("CI_JOB_ID"),
+            CiField::PipelineId => Some("CI_PIPELINE_ID"),
+            CiField::RegistryImage => Some("CI_REGISTRY_IMAGE"),
+        }
+    }
+}
+
+/// GitHub Actions, detected via `GITHUB_ACTIONS`
+#[derive(Debug, Default)]
+pub struct GitHubActionsCi;
+
+impl CiProvider for GitHubActionsCi {
+    fn name(&self) -> &'static str {
+        "GitHub Actions"
+    }
+
+    fn detect(&self) -> bool {
+        env::var("GITHUB_ACTIONS").is_ok()
+    }
+
+    fn var_name(&self, field: CiField) -> Option<&'static str> {
+        match field {
+            // `GITHUB_REF_NAME` is the tag name when `GITHUB_REF_TYPE=tag`;
+            // for branch builds it's just the branch name, which is the
+            // best GitHub Actions offers without inspecting `GITHUB_REF_TYPE`.
+            CiField::Tag => Some("GITHUB_REF_NAME"),
+            CiField::CommitSha => Some("GITHUB_SHA"),
+            CiField::JobId => Some("GITHUB_RUN_ID"),
+            CiField::PipelineId => Some("GITHUB_RUN_NUMBER"),
+            CiField::ShortSha | CiField::RegistryImage => None,
+        }
+    }
+}
+
+/// Drone CI, detected via `DRONE`
+#[derive(Debug, Default)]
+pub struct DroneCi;
+
+impl CiProvider for DroneCi {
+    fn name(&self) -> &'static str {
+        "Drone"
+    }
+
+    fn detect(&self) -> bool {
+        env::var("DRONE").is_ok()
+    }
+
+    fn var_name(&self, field: CiField) -> Option<&'static str> {
+        match field {
+            CiField::Tag => Some("DRONE_TAG"),
+            CiField::CommitSha => Some("DRONE_COMMIT_SHA"),
+            CiField::JobId | CiField::PipelineId => Some("DRONE_BUILD_NUMBER"),
+            CiField::ShortSha | CiField::RegistryImage => None,
+        }
+    }
+}
+
+/// Fallback provider for local builds or unrecognized CI systems: exposes no
+/// variables, so every [`CiInfo`] field is `None`. Always detects as active,
+/// since it's only ever consulted once every named provider has declined.
+#[derive(Debug, Default)]
+pub struct GenericCi;
+
+impl CiProvider for GenericCi {
+    fn name(&self) -> &'static str {
+        "local/unknown"
+    }
+
+    fn detect(&self) -> bool {
+        true
+    }
+
+    fn var_name(&self, _field: CiField) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Detect the active CI provider by probing each known provider in turn,
+/// falling back to [`GenericCi`] if none matches.
+#[must_use]
+pub fn detect_ci_provider() -> Box<dyn CiProvider> {
+    let providers: Vec<Box<dyn CiProvider>> =
+        vec![Box::new(GitLabCi), Box::new(GitHubActionsCi), Box::new(DroneCi)];
+
+    providers
+        .into_iter()
+        .find(|provider| provider.detect())
+        .unwrap_or_else(|| Box::new(GenericCi))
+}
+
+/// Output format for [`VersionGenerator::generate_as`], so the same crate
+/// can feed GitLab's `.env`-sourcing jobs, GitHub Actions' `$GITHUB_OUTPUT`
+/// convention, or a plain structured manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `.env`-style `KEY=value` lines (the original, GitLab-oriented format)
+    Env,
+    /// Pretty-printed JSON
+    Json,
+    /// Pretty-printed TOML
+    Toml,
+    /// Lowercase `key=value` lines appended to the file, matching the
+    /// `$GITHUB_OUTPUT` step-output convention
+    GithubActions,
+}
+
+/// Level at which to bump a semver-style `major.minor.patch[-prerelease]`
+/// version, as computed by [`VersionGenerator::bump`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BumpLevel {
+    /// Increment major, zero minor/patch; a prerelease version only drops
+    /// its prerelease suffix
+    Major,
+    /// Increment minor, zero patch; a prerelease version only drops its
+    /// prerelease suffix
+    Minor,
+    /// Increment patch; a prerelease version only drops its prerelease suffix
+    Patch,
+    /// Move to (or advance) a prerelease of the given id, e.g. `"rc"`
+    PreRelease(String),
+}
+
+/// A parsed `major.minor.patch[-prerelease]` version.
+struct SemVer {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    prerelease: Option<String>,
+}
+
+impl SemVer {
+    /// Parse `major.minor.patch` with an optional `-prerelease` suffix.
+    fn parse(version: &str) -> Result<Self> {
+        let (core, prerelease) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (version, None),
+        };
+
+        let mut parts = core.splitn(3, '.');
+        let parse_component = |part: Option<&str>| -> Result<u64> {
+            part.unwrap_or("0")
+                .parse()
+                .map_err(|_| BuilderError::validation(format!("'{version}' is not a valid semver version")))
+        };
+
+        Ok(Self {
+            major: parse_component(parts.next())?,
+            minor: parse_component(parts.next())?,
+            patch: parse_component(parts.next())?,
+            prerelease,
+        })
+    }
+
+    /// Render the `major.minor.patch` core, without any prerelease suffix.
+    fn core_string(&self) -> String {
+        format!("{}.{}.{}", self.major, self.minor, self.patch)
+    }
+
+}
+
+impl std::fmt::Display for SemVer {
+    /// Render the full version, including the prerelease suffix if present.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.prerelease {
+            Some(pre) => write!(f, "{}-{pre}", self.core_string()),
+            None => write!(f, "{}", self.core_string()),
+        }
+    }
+}
+
+/// Advance an existing prerelease tag for the `PreRelease(id)` bump level: if
+/// `existing` is already `"<id>.<n>"`, increment `n`; otherwise start fresh
+/// at `"<id>.1"`.
+fn bump_prerelease(existing: &str, id: &str) -> String {
+    match existing.rsplit_once('.') {
+        Some((prefix, num)) if prefix == id => match num.parse::<u64>() {
+            Ok(n) => format!("{id}.{}", n + 1),
+            Err(_) => format!("{id}.1"),
+        },
+        _ => format!("{id}.1"),
+    }
 }
 
 impl VersionGenerator {
@@ -41,30 +333,90 @@ impl VersionGenerator {
     pub fn new() -> Self {
         Self {
             fs_utils: FileSystemUtils::new(),
+            process_runner: ProcessRunner::new(false),
         }
     }
 
-    /// Generate version information file from PKGBUILD
+    /// Compute the next release version from an existing `VersionInfo`,
+    /// so CI pipelines don't have to hand-edit `pkgver`. Promoting a
+    /// prerelease to a final release (a `Major`/`Minor`/`Patch` bump on a
+    /// version that already carries a prerelease suffix) just drops the
+    /// suffix rather than incrementing the core again — the core already
+    /// reflects what that prerelease was heading towards. `pkg_release` is
+    /// always reset to `"1"` on a bump, since the new version starts its
+    /// own release cycle.
+    pub fn bump(&self, current: &VersionInfo, level: BumpLevel) -> Result<VersionInfo> {
+        let semver = SemVer::parse(&current.version)?;
+
+        let bumped = match level {
+            BumpLevel::Major | BumpLevel::Minor | BumpLevel::Patch if semver.prerelease.is_some() => {
+                SemVer { prerelease: None, ..semver }
+            }
+            BumpLevel::Major => SemVer { major: semver.major + 1, minor: 0, patch: 0, prerelease: None },
+            BumpLevel::Minor => SemVer { minor: semver.minor + 1, patch: 0, prerelease: None, ..semver },
+            BumpLevel::Patch => SemVer { patch: semver.patch + 1, prerelease: None, ..semver },
+            BumpLevel::PreRelease(id) => {
+                let prerelease = match &semver.prerelease {
+                    Some(existing) => bump_prerelease(existing, &id),
+                    None => format!("{id}.1"),
+                };
+                SemVer { prerelease: Some(prerelease), ..semver }
+            }
+        };
+
+        let mut info = current.clone();
+        info.version = bumped.to_string();
+        info.pkg_release = "1".to_string();
+        info.full_version = format!("{}-{}", info.version, info.pkg_release);
+        Ok(info)
+    }
+
+    /// Generate version information file from PKGBUILD, in the original
+    /// `.env` format.
     #[instrument(skip(self, pkgbuild, output_file))]
     pub fn generate<P: AsRef<Path>>(
         &self,
         pkgbuild: &PkgbuildInfo,
         output_file: P,
+    ) -> Result<VersionInfo> {
+        self.generate_as(pkgbuild, output_file, OutputFormat::Env)
+    }
+
+    /// Generate version information file from PKGBUILD in the given
+    /// [`OutputFormat`]. `GithubActions` appends to `output_file` instead of
+    /// overwriting it, matching the `$GITHUB_OUTPUT` step-output convention
+    /// where multiple steps in a job share one file.
+    #[instrument(skip(self, pkgbuild, output_file))]
+    pub fn generate_as<P: AsRef<Path>>(
+        &self,
+        pkgbuild: &PkgbuildInfo,
+        output_file: P,
+        format: OutputFormat,
     ) -> Result<VersionInfo> {
         let output_file = output_file.as_ref();
         info!(
-            "Generating version information to: {}",
+            "Generating version information ({:?}) to: {}",
+            format,
             output_file.display()
         );
 
         let version_info = self.create_version_info(pkgbuild)?;
-        let env_content = self.format_as_env_file(&version_info)?;
+        let content = match format {
+            OutputFormat::Env => self.format_as_env_file(&version_info)?,
+            OutputFormat::Json => self.format_as_json(&version_info)?,
+            OutputFormat::Toml => self.format_as_toml(&version_info)?,
+            OutputFormat::GithubActions => self.format_as_github_actions(&version_info)?,
+        };
 
-        self.fs_utils
-            .write_file(output_file, env_content.as_bytes())
-            .map_err(|e| {
-                crate::error::BuilderError::file_system("write", output_file.to_path_buf(), e)
-            })?;
+        if format == OutputFormat::GithubActions {
+            self.fs_utils
+                .append_file(output_file, content.as_bytes())
+                .map_err(|e| BuilderError::file_system("append", output_file.to_path_buf(), e))?;
+        } else {
+            self.fs_utils
+                .write_file(output_file, content.as_bytes())
+                .map_err(|e| BuilderError::file_system("write", output_file.to_path_buf(), e))?;
+        }
 
         info!("Version information generated successfully");
         debug!("Generated version info: {:?}", version_info);
@@ -74,8 +426,12 @@ impl VersionGenerator {
 
     /// Create version information from PKGBUILD and environment
     fn create_version_info(&self, pkgbuild: &PkgbuildInfo) -> Result<VersionInfo> {
-        let ci_commit_tag = env::var("CI_COMMIT_TAG").unwrap_or_else(|_| pkgbuild.version.clone());
-        let ci_job_id = env::var("CI_JOB_ID").unwrap_or_else(|_| "local".to_string());
+        let provider = detect_ci_provider();
+        debug!("Detected CI provider: {}", provider.name());
+        let ci = provider.collect();
+
+        let ci_commit_tag = ci.tag.unwrap_or_else(|| pkgbuild.version.clone());
+        let ci_job_id = ci.job_id.unwrap_or_else(|| "local".to_string());
         let build_date = Utc::now().to_rfc3339();
 
         let version_info = VersionInfo {
@@ -87,11 +443,101 @@ impl VersionGenerator {
             build_job_id: ci_job_id,
             build_date,
             arch: pkgbuild.arch.join(" "),
+            rustc_version: self.rustc_version(),
+            target_triple: self.host_target_triple(),
+            build_profile: Self::build_profile().to_string(),
+            git_sha: self.git_sha(&ci),
+            git_dirty: self.git_dirty(),
+            features: Self::enabled_features(),
         };
 
         Ok(version_info)
     }
 
+    /// `rustc --version`, e.g. `rustc 1.75.0 (82e1608df 2023-12-21)`, or
+    /// `"unknown"` if `rustc` couldn't be invoked.
+    fn rustc_version(&self) -> String {
+        self.process_runner
+            .command("rustc")
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|result| result.success)
+            .map(|result| result.stdout.trim().to_string())
+            .filter(|version| !version.is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Host target triple (e.g. `x86_64-unknown-linux-gnu`), parsed from the
+    /// `host:` line of `rustc -vV`, or `"unknown"` if unavailable.
+    fn host_target_triple(&self) -> String {
+        self.process_runner
+            .command("rustc")
+            .arg("-vV")
+            .output()
+            .ok()
+            .filter(|result| result.success)
+            .and_then(|result| {
+                result
+                    .stdout
+                    .lines()
+                    .find_map(|line| line.strip_prefix("host:").map(|host| host.trim().to_string()))
+            })
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// `"debug"` or `"release"`, from `debug_assertions` (no process call needed).
+    fn build_profile() -> &'static str {
+        if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        }
+    }
+
+    /// Git commit SHA: the detected CI provider's commit SHA when available,
+    /// otherwise `git rev-parse HEAD`, otherwise `"unknown"`.
+    fn git_sha(&self, ci: &CiInfo) -> String {
+        if let Some(sha) = &ci.commit_sha {
+            return sha.clone();
+        }
+
+        self.process_runner
+            .command("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|result| result.success)
+            .map(|result| result.stdout.trim().to_string())
+            .filter(|sha| !sha.is_empty())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Whether the working tree has uncommitted changes, per
+    /// `git status --porcelain`. Defaults to `false` if `git` can't be run
+    /// (e.g. outside a repository), since there's nothing to report as dirty.
+    fn git_dirty(&self) -> bool {
+        self.process_runner
+            .command("git")
+            .args(["status", "--porcelain"])
+            .output()
+            .ok()
+            .filter(|result| result.success)
+            .is_some_and(|result| !result.stdout.trim().is_empty())
+    }
+
+    /// Cargo features enabled for this build, collected from `CARGO_FEATURE_*`
+    /// environment variables. These are only set by cargo for build scripts
+    /// and proc-macro invocations, so this is best-effort and will be empty
+    /// for a binary invoked outside of `cargo build`/`cargo test`.
+    fn enabled_features() -> Vec<String> {
+        let mut features: Vec<String> = env::vars()
+            .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|name| name.to_lowercase()))
+            .collect();
+        features.sort();
+        features
+    }
+
     /// Format version information as environment file (.env format)
     fn format_as_env_file(&self, info: &VersionInfo) -> Result<String> {
         let content = format!(
@@ -103,6 +549,12 @@ TAG_VERSION={}
 BUILD_JOB_ID={}
 BUILD_DATE={}
 ARCH="{}"
+RUSTC_VERSION="{}"
+TARGET_TRIPLE={}
+BUILD_PROFILE={}
+GIT_SHA={}
+GIT_DIRTY={}
+FEATURES="{}"
 "#,
             info.version,
             info.pkg_release,
@@ -111,13 +563,58 @@ ARCH="{}"
             info.tag_version,
             info.build_job_id,
             info.build_date,
-            info.arch
+            info.arch,
+            info.rustc_version,
+            info.target_triple,
+            info.build_profile,
+            info.git_sha,
+            info.git_dirty,
+            info.features.join(" ")
+        );
+
+        Ok(content)
+    }
+
+    /// Format version information as pretty-printed JSON
+    fn format_as_json(&self, info: &VersionInfo) -> Result<String> {
+        serde_json::to_string_pretty(info)
+            .map_err(|e| BuilderError::config(format!("Failed to serialize version info to JSON: {e}")))
+    }
+
+    /// Format version information as pretty-printed TOML
+    fn format_as_toml(&self, info: &VersionInfo) -> Result<String> {
+        toml::to_string_pretty(info)
+            .map_err(|e| BuilderError::config(format!("Failed to serialize version info to TOML: {e}")))
+    }
+
+    /// Format version information as lowercase `key=value` lines, matching
+    /// the `$GITHUB_OUTPUT` step-output convention.
+    fn format_as_github_actions(&self, info: &VersionInfo) -> Result<String> {
+        let content = format!(
+            "version={}\npkg_release={}\nfull_version={}\npackage_name={}\ntag_version={}\nbuild_job_id={}\nbuild_date={}\narch={}\nrustc_version={}\ntarget_triple={}\nbuild_profile={}\ngit_sha={}\ngit_dirty={}\nfeatures={}\n",
+            info.version,
+            info.pkg_release,
+            info.full_version,
+            info.package_name,
+            info.tag_version,
+            info.build_job_id,
+            info.build_date,
+            info.arch,
+            info.rustc_version,
+            info.target_triple,
+            info.build_profile,
+            info.git_sha,
+            info.git_dirty,
+            info.features.join(" ")
         );
 
         Ok(content)
     }
 
-    /// Load version information from an existing file
+    /// Load version information from an existing file, auto-detecting the
+    /// format from its extension (`.json`, `.toml`, anything else is
+    /// treated as `KEY=value` lines, which covers both the `.env` format and
+    /// `$GITHUB_OUTPUT`-style files).
     #[instrument(skip(self, file_path))]
     pub fn load_from_file<P: AsRef<Path>>(&self, file_path: P) -> Result<VersionInfo> {
         let file_path = file_path.as_ref();
@@ -127,7 +624,13 @@ ARCH="{}"
             crate::error::BuilderError::file_system("read", file_path.to_path_buf(), e)
         })?;
 
-        self.parse_env_content(&content)
+        match file_path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str(&content)
+                .map_err(|e| BuilderError::config(format!("Failed to parse version info from JSON: {e}"))),
+            Some("toml") => toml::from_str(&content)
+                .map_err(|e| BuilderError::config(format!("Failed to parse version info from TOML: {e}"))),
+            _ => self.parse_env_content(&content),
+        }
     }
 
     /// Parse environment file content into VersionInfo
@@ -141,9 +644,11 @@ ARCH="{}"
             }
 
             if let Some((key, value)) = line.split_once('=') {
-                let key = key.trim();
+                // Upper-cased so this also reads back the lowercase
+                // `key=value` lines `format_as_github_actions` writes.
+                let key = key.trim().to_uppercase();
                 let value = value.trim().trim_matches('"');
-                env_vars.insert(key.to_string(), value.to_string());
+                env_vars.insert(key, value.to_string());
             }
         }
 
@@ -180,6 +685,29 @@ ARCH="{}"
                 .get("ARCH")
                 .cloned()
                 .unwrap_or_else(|| "any".to_string()),
+            rustc_version: env_vars
+                .get("RUSTC_VERSION")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+            target_triple: env_vars
+                .get("TARGET_TRIPLE")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+            build_profile: env_vars
+                .get("BUILD_PROFILE")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+            git_sha: env_vars
+                .get("GIT_SHA")
+                .cloned()
+                .unwrap_or_else(|| "unknown".to_string()),
+            git_dirty: env_vars
+                .get("GIT_DIRTY")
+                .is_some_and(|value| value == "true"),
+            features: env_vars
+                .get("FEATURES")
+                .map(|value| value.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
         };
 
         Ok(version_info)
@@ -275,6 +803,9 @@ mod tests {
             depends: vec![],
             make_depends: vec![],
             check_depends: vec![],
+            source: vec![],
+            sha256sums: vec![],
+            ..Default::default()
         }
     }
 
@@ -290,8 +821,10 @@ mod tests {
         let pkgbuild = create_test_pkgbuild();
 
         // Set test environment variables
+        EnvUtils::set_var("GITLAB_CI", "true");
         EnvUtils::set_var("CI_COMMIT_TAG", "v1.2.3");
         EnvUtils::set_var("CI_JOB_ID", "12345");
+        EnvUtils::set_var("CI_COMMIT_SHA", "deadbeef");
 
         let version_info = generator.create_version_info(&pkgbuild).unwrap();
 
@@ -302,10 +835,15 @@ mod tests {
         assert_eq!(version_info.tag_version, "v1.2.3");
         assert_eq!(version_info.build_job_id, "12345");
         assert_eq!(version_info.arch, "x86_64 aarch64");
+        // CI_COMMIT_SHA takes priority over invoking `git rev-parse HEAD`
+        assert_eq!(version_info.git_sha, "deadbeef");
+        assert_eq!(version_info.build_profile, "debug");
 
         // Clean up
+        EnvUtils::remove_var("GITLAB_CI");
         EnvUtils::remove_var("CI_COMMIT_TAG");
         EnvUtils::remove_var("CI_JOB_ID");
+        EnvUtils::remove_var("CI_COMMIT_SHA");
     }
 
     #[test]
@@ -320,6 +858,12 @@ mod tests {
             build_job_id: "123".to_string(),
             build_date: "2023-01-01T00:00:00Z".to_string(),
             arch: "x86_64".to_string(),
+            rustc_version: "rustc 1.75.0".to_string(),
+            target_triple: "x86_64-unknown-linux-gnu".to_string(),
+            build_profile: "debug".to_string(),
+            git_sha: "abc123".to_string(),
+            git_dirty: false,
+            features: vec![],
         };
 
         let content = generator.format_as_env_file(&version_info).unwrap();
@@ -350,6 +894,58 @@ mod tests {
         assert_eq!(generated_info.arch, loaded_info.arch);
     }
 
+    #[test]
+    fn test_generate_as_json_round_trip() {
+        let generator = VersionGenerator::new();
+        let pkgbuild = create_test_pkgbuild();
+        let temp_file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+
+        let generated_info = generator
+            .generate_as(&pkgbuild, temp_file.path(), OutputFormat::Json)
+            .unwrap();
+        let loaded_info = generator.load_from_file(temp_file.path()).unwrap();
+
+        assert_eq!(generated_info.version, loaded_info.version);
+        assert_eq!(generated_info.package_name, loaded_info.package_name);
+    }
+
+    #[test]
+    fn test_generate_as_toml_round_trip() {
+        let generator = VersionGenerator::new();
+        let pkgbuild = create_test_pkgbuild();
+        let temp_file = tempfile::Builder::new().suffix(".toml").tempfile().unwrap();
+
+        let generated_info = generator
+            .generate_as(&pkgbuild, temp_file.path(), OutputFormat::Toml)
+            .unwrap();
+        let loaded_info = generator.load_from_file(temp_file.path()).unwrap();
+
+        assert_eq!(generated_info.version, loaded_info.version);
+        assert_eq!(generated_info.package_name, loaded_info.package_name);
+    }
+
+    #[test]
+    fn test_generate_as_github_actions_appends_and_round_trips() {
+        let generator = VersionGenerator::new();
+        let pkgbuild = create_test_pkgbuild();
+        let temp_file = NamedTempFile::new().unwrap();
+
+        generator
+            .generate_as(&pkgbuild, temp_file.path(), OutputFormat::GithubActions)
+            .unwrap();
+        // A second step in the same job appends rather than overwriting.
+        let generated_info = generator
+            .generate_as(&pkgbuild, temp_file.path(), OutputFormat::GithubActions)
+            .unwrap();
+
+        let content = std::fs::read_to_string(temp_file.path()).unwrap();
+        assert_eq!(content.lines().filter(|line| *line == "version=1.2.3").count(), 2);
+
+        let loaded_info = generator.load_from_file(temp_file.path()).unwrap();
+        assert_eq!(generated_info.version, loaded_info.version);
+        assert_eq!(generated_info.package_name, loaded_info.package_name);
+    }
+
     #[test]
     fn test_env_utils() {
         // Test default value
@@ -388,4 +984,119 @@ mod tests {
         EnvUtils::remove_var("CI");
         EnvUtils::remove_var("GITLAB_CI");
     }
+
+    #[test]
+    fn test_detect_ci_provider_falls_back_to_generic() {
+        let provider = detect_ci_provider();
+        assert_eq!(provider.name(), "local/unknown");
+        assert_eq!(provider.collect(), CiInfo::default());
+    }
+
+    #[test]
+    fn test_detect_ci_provider_github_actions() {
+        EnvUtils::set_var("GITHUB_ACTIONS", "true");
+        EnvUtils::set_var("GITHUB_SHA", "cafef00d");
+        EnvUtils::set_var("GITHUB_RUN_ID", "42");
+
+        let provider = detect_ci_provider();
+        assert_eq!(provider.name(), "GitHub Actions");
+        let ci = provider.collect();
+        assert_eq!(ci.commit_sha.as_deref(), Some("cafef00d"));
+        assert_eq!(ci.job_id.as_deref(), Some("42"));
+        assert_eq!(ci.short_sha, None);
+
+        EnvUtils::remove_var("GITHUB_ACTIONS");
+        EnvUtils::remove_var("GITHUB_SHA");
+        EnvUtils::remove_var("GITHUB_RUN_ID");
+    }
+
+    #[test]
+    fn test_gitlab_provider_maps_native_vars() {
+        EnvUtils::set_var("GITLAB_CI", "true");
+        EnvUtils::set_var("CI_COMMIT_TAG", "v2.0.0");
+
+        let provider = detect_ci_provider();
+        assert_eq!(provider.name(), "GitLab CI");
+        assert_eq!(provider.collect().tag.as_deref(), Some("v2.0.0"));
+
+        EnvUtils::remove_var("GITLAB_CI");
+        EnvUtils::remove_var("CI_COMMIT_TAG");
+    }
+
+    fn version_info(version: &str) -> VersionInfo {
+        VersionInfo {
+            version: version.to_string(),
+            pkg_release: "3".to_string(),
+            full_version: format!("{version}-3"),
+            package_name: "test".to_string(),
+            tag_version: "v1.0.0".to_string(),
+            build_job_id: "123".to_string(),
+            build_date: "2023-01-01T00:00:00Z".to_string(),
+            arch: "x86_64".to_string(),
+            rustc_version: "rustc 1.75.0".to_string(),
+            target_triple: "x86_64-unknown-linux-gnu".to_string(),
+            build_profile: "debug".to_string(),
+            git_sha: "abc123".to_string(),
+            git_dirty: false,
+            features: vec![],
+        }
+    }
+
+    #[test]
+    fn test_bump_major_minor_patch() {
+        let generator = VersionGenerator::new();
+        let current = version_info("1.2.3");
+
+        let major = generator.bump(&current, BumpLevel::Major).unwrap();
+        assert_eq!(major.version, "2.0.0");
+
+        let minor = generator.bump(&current, BumpLevel::Minor).unwrap();
+        assert_eq!(minor.version, "1.3.0");
+
+        let patch = generator.bump(&current, BumpLevel::Patch).unwrap();
+        assert_eq!(patch.version, "1.2.4");
+
+        // A core bump always resets pkg_release and full_version
+        assert_eq!(patch.pkg_release, "1");
+        assert_eq!(patch.full_version, "1.2.4-1");
+    }
+
+    #[test]
+    fn test_bump_prerelease_starts_fresh() {
+        let generator = VersionGenerator::new();
+        let current = version_info("1.2.3");
+
+        let bumped = generator.bump(&current, BumpLevel::PreRelease("rc".to_string())).unwrap();
+        assert_eq!(bumped.version, "1.2.3-rc.1");
+    }
+
+    #[test]
+    fn test_bump_prerelease_increments_existing() {
+        let generator = VersionGenerator::new();
+        let current = version_info("1.2.3-rc.3");
+
+        let bumped = generator.bump(&current, BumpLevel::PreRelease("rc".to_string())).unwrap();
+        assert_eq!(bumped.version, "1.2.3-rc.4");
+    }
+
+    #[test]
+    fn test_bump_core_on_prerelease_just_drops_suffix() {
+        let generator = VersionGenerator::new();
+        let current = version_info("1.2.3-rc.1");
+
+        // Promoting a prerelease to final doesn't bump the core again --
+        // it's already the version the prerelease was heading towards.
+        let major = generator.bump(&current, BumpLevel::Major).unwrap();
+        assert_eq!(major.version, "1.2.3");
+
+        let patch = generator.bump(&current, BumpLevel::Patch).unwrap();
+        assert_eq!(patch.version, "1.2.3");
+    }
+
+    #[test]
+    fn test_bump_rejects_non_semver_version() {
+        let generator = VersionGenerator::new();
+        let current = version_info("not-a-version");
+        assert!(generator.bump(&current, BumpLevel::Patch).is_err());
+    }
 }
@@ -0,0 +1,134 @@
+//! Structured, machine-readable build events
+//!
+//! Newline-delimited JSON records describing lifecycle events, analogous to
+//! cargo's `--message-format=json`. Opt-in and routed through a small
+//! [`EventSink`] abstraction so the default `tracing` human output is
+//! unaffected either way.
+
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::warn;
+
+/// A single build lifecycle event, serialized as one JSON object per line
+/// when event emission is enabled.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum BuildEvent {
+    /// A command was spawned
+    CommandStarted {
+        /// The command and arguments as they were invoked
+        command: String,
+    },
+    /// A command finished, successfully or not
+    CommandFinished {
+        /// The command and arguments as they were invoked
+        command: String,
+        /// Exit code, if the process ran to completion
+        exit_code: Option<i32>,
+        /// Bytes of captured stdout (0 when stdio was inherited, not captured)
+        stdout_len: usize,
+        /// Bytes of captured stderr (0 when stdio was inherited, not captured)
+        stderr_len: usize,
+        /// How long the command ran for
+        duration_ms: u128,
+    },
+    /// An artifact was collected
+    ArtifactCollected {
+        /// Original file path
+        source: String,
+        /// Destination path
+        destination: String,
+    },
+    /// The build failed
+    BuildError {
+        /// Human-readable error description
+        message: String,
+    },
+}
+
+/// Destination for [`BuildEvent`]s. The default sink ([`NoopSink`]) makes
+/// emitting an event free unless JSON output was requested.
+pub trait EventSink: Send + Sync {
+    /// Emit a single event
+    fn emit(&self, event: &BuildEvent);
+}
+
+/// Discards every event; used when JSON events aren't requested.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopSink;
+
+impl EventSink for NoopSink {
+    fn emit(&self, _event: &BuildEvent) {}
+}
+
+/// Writes one JSON object per line to stdout.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonStdoutSink;
+
+impl EventSink for JsonStdoutSink {
+    fn emit(&self, event: &BuildEvent) {
+        match serde_json::to_string(event) {
+            Ok(line) => println!("{line}"),
+            Err(e) => warn!("Failed to serialize build event: {}", e),
+        }
+    }
+}
+
+/// Pick the sink implied by `--message-format`: [`JsonStdoutSink`] when JSON
+/// events were requested, [`NoopSink`] otherwise.
+#[must_use]
+pub fn sink_for(json_events: bool) -> Arc<dyn EventSink> {
+    if json_events {
+        Arc::new(JsonStdoutSink)
+    } else {
+        Arc::new(NoopSink)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<BuildEvent>>);
+
+    impl EventSink for RecordingSink {
+        fn emit(&self, event: &BuildEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    #[test]
+    fn test_noop_sink_discards_events() {
+        let sink = NoopSink;
+        sink.emit(&BuildEvent::BuildError {
+            message: "irrelevant".to_string(),
+        });
+    }
+
+    #[test]
+    fn test_sink_for_selects_noop_by_default() {
+        let sink = sink_for(false);
+        // Exercised purely for the "doesn't panic" contract; NoopSink discards.
+        sink.emit(&BuildEvent::CommandStarted {
+            command: "echo hi".to_string(),
+        });
+    }
+
+    #[test]
+    fn test_recording_sink_observes_emitted_events() {
+        let sink = RecordingSink::default();
+        sink.emit(&BuildEvent::ArtifactCollected {
+            source: "a.pkg.tar.zst".to_string(),
+            destination: "artifacts/a.pkg.tar.zst".to_string(),
+        });
+
+        let recorded = sink.0.lock().unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert!(matches!(
+            recorded[0],
+            BuildEvent::ArtifactCollected { .. }
+        ));
+    }
+}
@@ -3,9 +3,9 @@
 //! Provides safe file operations with proper error handling.
 
 use std::fs;
-use std::io;
-use std::path::Path;
-use tracing::{debug, instrument};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
 
 /// Utility struct for file system operations
 #[derive(Debug)]
@@ -36,15 +36,68 @@ impl FileSystemUtils {
         
         // Copy the file
         let bytes_copied = fs::copy(src, dst)?;
-        
-        // Copy permissions
+
+        // Copy permissions and timestamps, so collected artifacts keep the
+        // mode/mtime makepkg gave them rather than picking up "now"
         let metadata = fs::metadata(src)?;
+
+        // Set the times before the mode, since opening `dst` for writing
+        // would fail once a read-only mode has been applied
+        let times = fs::FileTimes::new()
+            .set_accessed(metadata.accessed()?)
+            .set_modified(metadata.modified()?);
+        fs::File::options().write(true).open(dst)?.set_times(times)?;
         fs::set_permissions(dst, metadata.permissions())?;
-        
+
         debug!("Successfully copied {} bytes", bytes_copied);
         Ok(bytes_copied)
     }
 
+    /// Recursively copy the tree rooted at `src` to `dst`, recreating the
+    /// directory structure and copying each regular file via [`Self::copy_file`]
+    /// (so permissions/timestamps carry over). Symlinks are recreated as
+    /// symlinks to the same target rather than followed, so a link into the
+    /// tree being copied can't cause infinite recursion.
+    ///
+    /// A single unreadable entry doesn't abort the walk: failures are
+    /// accumulated and, if any occurred, returned together as one combined
+    /// error after every other entry has still been copied. Returns the
+    /// total bytes copied across all regular files.
+    #[instrument(skip(self))]
+    pub fn copy_dir_all<P: AsRef<Path> + std::fmt::Debug, Q: AsRef<Path> + std::fmt::Debug>(
+        &self,
+        src: P,
+        dst: Q,
+    ) -> io::Result<u64> {
+        let src = src.as_ref();
+        let dst = dst.as_ref();
+
+        debug!("Copying directory tree: {} -> {}", src.display(), dst.display());
+
+        let mut total_bytes = 0u64;
+        let mut attempted = 0usize;
+        let mut failures = Vec::new();
+
+        copy_dir_contents(self, src, dst, &mut total_bytes, &mut attempted, &mut failures);
+
+        if failures.is_empty() {
+            debug!("Copied {} bytes across directory tree", total_bytes);
+            return Ok(total_bytes);
+        }
+
+        let detail = failures
+            .iter()
+            .map(|(path, e)| format!("{}: {}", path.display(), e))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(io::Error::other(format!(
+            "{} of {} entries failed to copy: {}",
+            failures.len(),
+            attempted,
+            detail
+        )))
+    }
+
     /// Move a file from source to destination
     #[instrument(skip(self))]
     pub fn move_file<P: AsRef<Path> + std::fmt::Debug, Q: AsRef<Path> + std::fmt::Debug>(
@@ -123,6 +176,59 @@ impl FileSystemUtils {
         }
     }
 
+    /// Recursively remove `dir` and everything inside it, refusing to
+    /// follow or delete through any symlink whose target resolves outside
+    /// `boundary`. Unlike `remove_dir_all_if_exists`, this walks the tree
+    /// entry by entry and keeps going on a per-entry failure instead of
+    /// aborting on the first one, returning every path it couldn't remove
+    /// so the caller can report them.
+    #[instrument(skip(self))]
+    pub fn remove_dir_all_guarded<P: AsRef<Path> + std::fmt::Debug, B: AsRef<Path> + std::fmt::Debug>(
+        &self,
+        dir: P,
+        boundary: B,
+    ) -> Vec<(PathBuf, io::Error)> {
+        let dir = dir.as_ref();
+        let mut failures = Vec::new();
+
+        if !dir.exists() {
+            debug!("Directory does not exist: {}", dir.display());
+            return failures;
+        }
+
+        let boundary = match fs::canonicalize(boundary.as_ref()) {
+            Ok(path) => path,
+            Err(e) => {
+                failures.push((boundary.as_ref().to_path_buf(), e));
+                return failures;
+            }
+        };
+
+        // `dir` itself may be a symlink; never walk into a target outside `boundary`
+        match fs::canonicalize(dir) {
+            Ok(resolved) if resolved.starts_with(&boundary) => {}
+            Ok(_) => {
+                warn!("Refusing to remove directory resolving outside {}: {}", boundary.display(), dir.display());
+                failures.push((dir.to_path_buf(), io::Error::other("resolves outside the boundary directory")));
+                return failures;
+            }
+            Err(e) => {
+                failures.push((dir.to_path_buf(), e));
+                return failures;
+            }
+        }
+
+        remove_dir_contents(dir, &boundary, &mut failures);
+
+        if let Err(e) = fs::remove_dir(dir) {
+            if e.kind() != io::ErrorKind::NotFound {
+                failures.push((dir.to_path_buf(), e));
+            }
+        }
+
+        failures
+    }
+
     /// Check if a path exists and is a file
     pub fn is_file<P: AsRef<Path>>(&self, path: P) -> bool {
         path.as_ref().is_file()
@@ -162,6 +268,30 @@ impl FileSystemUtils {
         Ok(())
     }
 
+    /// Append content to a file, creating it (and any parent directories) if
+    /// it doesn't exist yet. Used for step-output conventions like
+    /// `$GITHUB_OUTPUT`, where multiple steps in the same job append to one
+    /// shared file rather than overwriting it.
+    #[instrument(skip(self, contents))]
+    pub fn append_file<P: AsRef<Path> + std::fmt::Debug, C: AsRef<[u8]>>(
+        &self,
+        path: P,
+        contents: C,
+    ) -> io::Result<()> {
+        let path = path.as_ref();
+
+        debug!("Appending to file: {}", path.display());
+
+        if let Some(parent) = path.parent() {
+            self.create_dir_all(parent)?;
+        }
+
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(contents.as_ref())?;
+        debug!("File appended successfully");
+        Ok(())
+    }
+
     /// Read file contents as string
     #[instrument(skip(self))]
     pub fn read_file_to_string<P: AsRef<Path> + std::fmt::Debug>(&self, path: P) -> io::Result<String> {
@@ -190,6 +320,145 @@ impl Default for FileSystemUtils {
     }
 }
 
+/// Walk `src`'s entries, recreating them under `dst`: regular files are
+/// copied via `fs_utils.copy_file`, directories are recursed into, and
+/// symlinks are recreated pointing at the same target. Failures are
+/// accumulated in `failures` instead of aborting the walk.
+fn copy_dir_contents(
+    fs_utils: &FileSystemUtils,
+    src: &Path,
+    dst: &Path,
+    total_bytes: &mut u64,
+    attempted: &mut usize,
+    failures: &mut Vec<(PathBuf, io::Error)>,
+) {
+    if let Err(e) = fs::create_dir_all(dst) {
+        failures.push((dst.to_path_buf(), e));
+        return;
+    }
+
+    let entries = match fs::read_dir(src) {
+        Ok(entries) => entries,
+        Err(e) => {
+            failures.push((src.to_path_buf(), e));
+            return;
+        }
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                failures.push((src.to_path_buf(), e));
+                continue;
+            }
+        };
+        let src_path = entry.path();
+        let dst_path = dst.join(entry.file_name());
+        *attempted += 1;
+
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(e) => {
+                failures.push((src_path, e));
+                continue;
+            }
+        };
+
+        if file_type.is_symlink() {
+            if let Err(e) = copy_symlink(&src_path, &dst_path) {
+                failures.push((src_path, e));
+            }
+        } else if file_type.is_dir() {
+            copy_dir_contents(fs_utils, &src_path, &dst_path, total_bytes, attempted, failures);
+        } else {
+            match fs_utils.copy_file(&src_path, &dst_path) {
+                Ok(bytes) => *total_bytes += bytes,
+                Err(e) => failures.push((src_path, e)),
+            }
+        }
+    }
+}
+
+/// Recreate the symlink at `src` at `dst`, pointing at the same target,
+/// instead of following it (which could recurse forever on a link back
+/// into the tree being copied).
+#[cfg(unix)]
+fn copy_symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    let target = fs::read_link(src)?;
+    std::os::unix::fs::symlink(target, dst)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    let target = fs::read_link(src)?;
+    if fs::metadata(src).map(|m| m.is_dir()).unwrap_or(false) {
+        std::os::windows::fs::symlink_dir(target, dst)
+    } else {
+        std::os::windows::fs::symlink_file(target, dst)
+    }
+}
+
+/// Walk `dir`'s entries, removing files and recursing into subdirectories.
+/// Symlinks are never followed for traversal; one is only removed (as the
+/// link itself, never its target) once its resolved target is confirmed to
+/// stay within `boundary`. Failures are collected rather than propagated so
+/// one bad entry doesn't stop the rest of the tree from being cleaned up.
+fn remove_dir_contents(dir: &Path, boundary: &Path, failures: &mut Vec<(PathBuf, io::Error)>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            failures.push((dir.to_path_buf(), e));
+            return;
+        }
+    };
+
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                failures.push((dir.to_path_buf(), e));
+                continue;
+            }
+        };
+        let path = entry.path();
+
+        let file_type = match entry.file_type() {
+            Ok(file_type) => file_type,
+            Err(e) => {
+                failures.push((path, e));
+                continue;
+            }
+        };
+
+        if file_type.is_symlink() {
+            match fs::canonicalize(&path) {
+                Ok(target) if target.starts_with(boundary) => {
+                    if let Err(e) = fs::remove_file(&path) {
+                        failures.push((path, e));
+                    }
+                }
+                Ok(_) => {
+                    warn!(
+                        "Refusing to remove symlink pointing outside the working directory: {}",
+                        path.display()
+                    );
+                }
+                Err(e) => failures.push((path, e)),
+            }
+        } else if file_type.is_dir() {
+            remove_dir_contents(&path, boundary, failures);
+            if let Err(e) = fs::remove_dir(&path) {
+                if e.kind() != io::ErrorKind::NotFound {
+                    failures.push((path, e));
+                }
+            }
+        } else if let Err(e) = fs::remove_file(&path) {
+            failures.push((path, e));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,19 +487,137 @@ mod tests {
     fn test_move_file() {
         let temp_dir = TempDir::new().unwrap();
         let fs_utils = FileSystemUtils::new();
-        
+
         let src = temp_dir.path().join("source.txt");
         let dst = temp_dir.path().join("dest.txt");
-        
+
         fs::write(&src, "test content").unwrap();
-        
+
         fs_utils.move_file(&src, &dst).unwrap();
-        
+
         assert!(dst.exists());
         assert!(!src.exists()); // Source should be removed
         assert_eq!(fs::read_to_string(&dst).unwrap(), "test content");
     }
 
+    #[test]
+    fn test_copy_file_preserves_mtime() {
+        let temp_dir = TempDir::new().unwrap();
+        let fs_utils = FileSystemUtils::new();
+
+        let src = temp_dir.path().join("source.txt");
+        let dst = temp_dir.path().join("dest.txt");
+
+        fs::write(&src, "test content").unwrap();
+        let src_mtime = fs::metadata(&src).unwrap().modified().unwrap();
+
+        fs_utils.copy_file(&src, &dst).unwrap();
+        let dst_mtime = fs::metadata(&dst).unwrap().modified().unwrap();
+
+        assert_eq!(src_mtime, dst_mtime);
+    }
+
+    #[test]
+    fn test_remove_dir_all_guarded_removes_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let fs_utils = FileSystemUtils::new();
+
+        let root = temp_dir.path().join("pkg");
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("file.txt"), "content").unwrap();
+        fs::write(root.join("nested").join("inner.txt"), "content").unwrap();
+
+        let failures = fs_utils.remove_dir_all_guarded(&root, temp_dir.path());
+
+        assert!(failures.is_empty());
+        assert!(!root.exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_remove_dir_all_guarded_refuses_symlink_outside_boundary() {
+        let temp_dir = TempDir::new().unwrap();
+        let fs_utils = FileSystemUtils::new();
+
+        let outside_target = temp_dir.path().join("outside");
+        fs::create_dir_all(&outside_target).unwrap();
+        fs::write(outside_target.join("secret.txt"), "do not delete").unwrap();
+
+        let root = temp_dir.path().join("pkg");
+        fs::create_dir_all(&root).unwrap();
+        std::os::unix::fs::symlink(&outside_target, root.join("escape")).unwrap();
+
+        fs_utils.remove_dir_all_guarded(&root, &root);
+
+        assert!(outside_target.join("secret.txt").exists());
+    }
+
+    #[test]
+    fn test_copy_dir_all_copies_nested_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let fs_utils = FileSystemUtils::new();
+
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("nested")).unwrap();
+        fs::write(src.join("top.txt"), "top").unwrap();
+        fs::write(src.join("nested").join("inner.txt"), "inner content").unwrap();
+
+        let dst = temp_dir.path().join("dst");
+        let bytes_copied = fs_utils.copy_dir_all(&src, &dst).unwrap();
+
+        assert_eq!(bytes_copied, "top".len() as u64 + "inner content".len() as u64);
+        assert_eq!(fs::read_to_string(dst.join("top.txt")).unwrap(), "top");
+        assert_eq!(
+            fs::read_to_string(dst.join("nested").join("inner.txt")).unwrap(),
+            "inner content"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_copy_dir_all_reports_unreadable_file_but_copies_rest() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let fs_utils = FileSystemUtils::new();
+
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(&src).unwrap();
+        let locked = src.join("locked.txt");
+        fs::write(&locked, "secret").unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        fs::write(src.join("readable.txt"), "ok").unwrap();
+
+        let dst = temp_dir.path().join("dst");
+        let result = fs_utils.copy_dir_all(&src, &dst);
+
+        // Restore permissions so TempDir can clean up the file afterwards
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o644)).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(dst.join("readable.txt")).unwrap(), "ok");
+        assert!(!dst.join("locked.txt").exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_copy_dir_all_recreates_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let fs_utils = FileSystemUtils::new();
+
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("target.txt"), "data").unwrap();
+        std::os::unix::fs::symlink("target.txt", src.join("link.txt")).unwrap();
+
+        let dst = temp_dir.path().join("dst");
+        fs_utils.copy_dir_all(&src, &dst).unwrap();
+
+        let link = dst.join("link.txt");
+        assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+        assert_eq!(fs::read_link(&link).unwrap(), Path::new("target.txt"));
+    }
+
     #[test]
     fn test_create_nested_directories() {
         let temp_dir = TempDir::new().unwrap();
@@ -275,6 +662,20 @@ mod tests {
         assert_eq!(content, read_content);
     }
 
+    #[test]
+    fn test_append_file_creates_and_appends() {
+        let temp_dir = TempDir::new().unwrap();
+        let fs_utils = FileSystemUtils::new();
+
+        let file_path = temp_dir.path().join("subdir").join("output.txt");
+
+        fs_utils.append_file(&file_path, "first\n").unwrap();
+        fs_utils.append_file(&file_path, "second\n").unwrap();
+
+        let content = fs_utils.read_file_to_string(&file_path).unwrap();
+        assert_eq!(content, "first\nsecond\n");
+    }
+
     #[test]
     fn test_file_size() {
         let temp_dir = TempDir::new().unwrap();
@@ -4,9 +4,14 @@
 //! and environment handling.
 
 pub mod env;
+pub mod events;
 pub mod fs;
 pub mod process;
+pub mod strings;
+pub mod transaction;
 
-pub use env::VersionGenerator;
+pub use env::{BumpLevel, CiField, CiInfo, CiProvider, VersionGenerator, detect_ci_provider};
+pub use events::BuildEvent;
 pub use fs::FileSystemUtils;
-pub use process::ProcessRunner;
\ No newline at end of file
+pub use process::ProcessRunner;
+pub use transaction::Transaction;
\ No newline at end of file
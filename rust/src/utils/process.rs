@@ -3,15 +3,54 @@
 //! Provides safe process execution with proper error handling and logging.
 
 use crate::error::{BuilderError, Result};
-use std::process::{Command, Stdio};
+use crate::utils::events::{BuildEvent, EventSink, NoopSink};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::{debug, info, instrument, warn};
 
+#[cfg(unix)]
+const SIGTERM: i32 = 15;
+#[cfg(unix)]
+const SIGKILL: i32 = 9;
+
+/// How long a timed-out process is given to exit after `SIGTERM` before
+/// `SIGKILL` is sent.
+const TERMINATION_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// How often a running child is polled while waiting for it to finish or
+/// for its timeout to elapse.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Utility for running external processes
-#[derive(Debug)]
 pub struct ProcessRunner {
     debug: bool,
+    /// When set, commands are recorded here instead of being executed
+    /// (`--dry-run` / build-plan mode). See [`ProcessRunner::new_dry_run`].
+    plan: Option<Arc<Mutex<Vec<PlannedInvocation>>>>,
+    /// Privilege-escalation tool to prefix `.elevated()` commands with.
+    /// `None` means auto-detect from [`ESCALATION_CANDIDATES`] at use time.
+    escalation: Option<String>,
+    /// Where `command-started`/`command-finished` events are sent. Defaults
+    /// to [`NoopSink`], so emitting an event costs nothing unless
+    /// `--message-format=json` was requested.
+    event_sink: Arc<dyn EventSink>,
 }
 
+impl std::fmt::Debug for ProcessRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProcessRunner")
+            .field("debug", &self.debug)
+            .field("escalation", &self.escalation)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Privilege-escalation tools tried in order when none is configured
+const ESCALATION_CANDIDATES: &[&str] = &["sudo", "run0", "pkexec", "doas"];
+
 /// Result of a process execution
 #[derive(Debug)]
 pub struct ProcessResult {
@@ -25,130 +64,89 @@ pub struct ProcessResult {
     pub success: bool,
 }
 
+/// A single command invocation recorded instead of executed, used by
+/// `--dry-run` / build-plan mode to let callers preview what would run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedInvocation {
+    /// The program that would be executed
+    pub program: String,
+    /// Arguments that would be passed to the program
+    pub args: Vec<String>,
+    /// Environment variables that would be set for the child process
+    pub env: Vec<(String, String)>,
+    /// Working directory the command would run in, if overridden
+    pub cwd: Option<PathBuf>,
+}
+
 impl ProcessRunner {
     /// Create a new process runner
     #[must_use]
-    pub const fn new(debug: bool) -> Self {
-        Self { debug }
+    pub fn new(debug: bool) -> Self {
+        Self {
+            debug,
+            plan: None,
+            escalation: None,
+            event_sink: Arc::new(NoopSink),
+        }
     }
 
-    /// Run a command with arguments, inheriting stdout/stderr
-    #[instrument(skip(self))]
-    pub fn run_command(&self, command: &str, args: &[&str]) -> Result<()> {
-        self.run_command_with_env(command, args, &[])
+    /// Create a process runner in dry-run mode: rather than spawning a child
+    /// process, every invocation built via [`command`](Self::command) is
+    /// appended to the returned shared plan.
+    #[must_use]
+    pub fn new_dry_run(debug: bool) -> (Self, Arc<Mutex<Vec<PlannedInvocation>>>) {
+        let plan = Arc::new(Mutex::new(Vec::new()));
+        (
+            Self {
+                debug,
+                plan: Some(Arc::clone(&plan)),
+                escalation: None,
+                event_sink: Arc::new(NoopSink),
+            },
+            plan,
+        )
     }
 
-    /// Run a command with arguments and environment variables
-    #[instrument(skip(self, env_vars))]
-    pub fn run_command_with_env(
-        &self,
-        command: &str,
-        args: &[&str],
-        env_vars: &[(String, String)],
-    ) -> Result<()> {
-        let cmd_str = format!("{} {}", command, args.join(" "));
-
-        if self.debug {
-            debug!("Running command: {}", cmd_str);
-            if !env_vars.is_empty() {
-                debug!("Environment variables: {:?}", env_vars);
-            }
-        } else {
-            info!("+ {}", cmd_str);
-        }
-
-        let mut cmd = Command::new(command);
-        cmd.args(args)
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit());
-
-        // Add environment variables
-        for (key, value) in env_vars {
-            cmd.env(key, value);
-        }
-
-        let status = cmd.status().map_err(|e| {
-            BuilderError::process(
-                cmd_str.clone(),
-                None,
-                String::new(),
-                format!("Failed to execute command: {e}"),
-            )
-        })?;
-
-        if !status.success() {
-            let exit_code = status.code();
-            return Err(BuilderError::process(
-                cmd_str,
-                exit_code,
-                String::new(),
-                format!("Command failed with exit code: {exit_code:?}"),
-            ));
-        }
-
-        debug!("Command completed successfully");
-        Ok(())
+    /// Use `tool` (e.g. `"sudo"`, `"doas"`) to prefix `.elevated()` commands
+    /// instead of auto-detecting one from [`ESCALATION_CANDIDATES`].
+    #[must_use]
+    pub fn with_escalation(mut self, tool: impl Into<String>) -> Self {
+        self.escalation = Some(tool.into());
+        self
     }
 
-    /// Run a command and capture its output
-    #[instrument(skip(self))]
-    pub fn run_command_with_output(&self, command: &str, args: &[&str]) -> Result<ProcessResult> {
-        self.run_command_with_output_and_env(command, args, &[])
+    /// Route lifecycle events to `sink` instead of the default [`NoopSink`]
+    /// (e.g. [`JsonStdoutSink`](crate::utils::events::JsonStdoutSink) when
+    /// `--message-format=json` was requested).
+    #[must_use]
+    pub fn with_event_sink(mut self, sink: Arc<dyn EventSink>) -> Self {
+        self.event_sink = sink;
+        self
     }
 
-    /// Run a command with environment variables and capture output
-    #[instrument(skip(self, env_vars))]
-    pub fn run_command_with_output_and_env(
-        &self,
-        command: &str,
-        args: &[&str],
-        env_vars: &[(String, String)],
-    ) -> Result<ProcessResult> {
-        let cmd_str = format!("{} {}", command, args.join(" "));
-
-        debug!("Running command with output capture: {}", cmd_str);
-
-        let mut cmd = Command::new(command);
-        cmd.args(args).stdout(Stdio::piped()).stderr(Stdio::piped());
-
-        // Add environment variables
-        for (key, value) in env_vars {
-            cmd.env(key, value);
-        }
-
-        let output = cmd.output().map_err(|e| {
-            BuilderError::process(
-                cmd_str.clone(),
-                None,
-                String::new(),
-                format!("Failed to execute command: {e}"),
-            )
-        })?;
-
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        let success = output.status.success();
-        let exit_code = output.status.code();
-
-        debug!(
-            "Command finished: success={}, exit_code={:?}, stdout_len={}, stderr_len={}",
-            success,
-            exit_code,
-            stdout.len(),
-            stderr.len()
-        );
+    /// Emit a lifecycle event to the configured sink
+    fn emit(&self, event: BuildEvent) {
+        self.event_sink.emit(&event);
+    }
 
-        if !success {
-            debug!("Command stderr: {}", stderr);
-            return Err(BuilderError::process(cmd_str, exit_code, stdout, stderr));
+    /// Resolve the escalation tool to prefix elevated commands with: the
+    /// configured tool if set, otherwise the first available candidate found
+    /// via [`command_exists`](Self::command_exists).
+    fn resolve_escalation_tool(&self) -> Option<String> {
+        if let Some(tool) = &self.escalation {
+            return Some(tool.clone());
         }
+        ESCALATION_CANDIDATES
+            .iter()
+            .find(|candidate| self.command_exists(candidate))
+            .map(|candidate| (*candidate).to_string())
+    }
 
-        Ok(ProcessResult {
-            exit_code,
-            stdout,
-            stderr,
-            success,
-        })
+    /// Start building a command to run `program`, accumulating args/env/cwd
+    /// through chained methods and executing via [`ProcessBuilder::status`] or
+    /// [`ProcessBuilder::output`].
+    pub fn command(&self, program: impl Into<String>) -> ProcessBuilder<'_> {
+        ProcessBuilder::new(self, program)
     }
 
     /// Check if a command exists in PATH
@@ -175,45 +173,17 @@ impl ProcessRunner {
         }
     }
 
-    /// Run multiple commands in sequence
-    #[instrument(skip(self, commands))]
-    pub fn run_commands_sequence(
-        &self,
-        commands: &[(&str, &[&str])],
-    ) -> Result<Vec<ProcessResult>> {
-        let mut results = Vec::new();
-
-        for (i, (command, args)) in commands.iter().enumerate() {
-            debug!(
-                "Running command {} of {}: {}",
-                i + 1,
-                commands.len(),
-                command
-            );
-
-            match self.run_command_with_output(command, args) {
-                Ok(result) => {
-                    debug!("Command {} completed successfully", i + 1);
-                    results.push(result);
-                }
-                Err(e) => {
-                    warn!("Command {} failed: {}", i + 1, e);
-                    return Err(e);
-                }
-            }
-        }
-
-        info!("All {} commands completed successfully", commands.len());
-        Ok(results)
-    }
-
     /// Kill a process by PID (Unix only)
     #[cfg(unix)]
     #[instrument(skip(self))]
     pub fn kill_process(&self, pid: u32, signal: i32) -> Result<()> {
         debug!("Killing process {} with signal {}", pid, signal);
 
-        let result = self.run_command("kill", &[&format!("-{signal}"), &pid.to_string()]);
+        let result = self
+            .command("kill")
+            .arg(format!("-{signal}"))
+            .arg(pid.to_string())
+            .status();
 
         match result {
             Ok(()) => {
@@ -233,7 +203,12 @@ impl ProcessRunner {
     pub fn get_processes_by_name(&self, name: &str) -> Result<Vec<u32>> {
         debug!("Getting processes by name: {}", name);
 
-        let result = self.run_command_with_output("pgrep", &[name])?;
+        let result = match self.command("pgrep").arg(name).output() {
+            Ok(result) => result,
+            // pgrep exits with status 1 when no process matches
+            Err(BuilderError::Process { exit_code: Some(1), .. }) => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
 
         let pids: Vec<u32> = result
             .stdout
@@ -249,6 +224,119 @@ impl ProcessRunner {
         );
         Ok(pids)
     }
+
+    /// Wait for `child` to exit, enforcing `timeout` if given: on expiry the
+    /// child's whole process group is sent `SIGTERM`, given
+    /// [`TERMINATION_GRACE_PERIOD`] to exit, then sent `SIGKILL`. Every
+    /// spawned process is placed in its own process group (see
+    /// `ProcessBuilder::build_command`), so this reaps exactly that child's
+    /// descendants (e.g. makepkg's compiler invocations) without touching
+    /// sibling jobs or unrelated processes that happen to share its name.
+    fn wait_for_child(
+        &self,
+        mut child: Child,
+        _program: &str,
+        cmd_str: &str,
+        timeout: Option<Duration>,
+    ) -> Result<std::process::ExitStatus> {
+        let Some(timeout) = timeout else {
+            return child.wait().map_err(|e| {
+                BuilderError::process(
+                    cmd_str.to_string(),
+                    None,
+                    String::new(),
+                    format!("Failed to wait for command: {e}"),
+                )
+            });
+        };
+
+        let start = Instant::now();
+        loop {
+            if let Some(status) = child.try_wait().map_err(|e| {
+                BuilderError::process(
+                    cmd_str.to_string(),
+                    None,
+                    String::new(),
+                    format!("Failed to poll command: {e}"),
+                )
+            })? {
+                return Ok(status);
+            }
+
+            if start.elapsed() >= timeout {
+                warn!(
+                    "Command exceeded timeout of {:?}, terminating: {}",
+                    timeout, cmd_str
+                );
+                self.terminate_then_kill(&mut child);
+                return Err(BuilderError::timeout(cmd_str.to_string(), timeout));
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Send `SIGTERM` to the child's whole process group, wait
+    /// [`TERMINATION_GRACE_PERIOD`], then `SIGKILL` the group if it's still
+    /// alive. The group (rather than just the child's pid) catches
+    /// descendants a killed build tool leaves behind (e.g. makepkg's
+    /// compiler invocations), since [`ProcessBuilder::build_command`] places
+    /// every spawned process in its own group headed by the child itself.
+    #[cfg(unix)]
+    fn terminate_then_kill(&self, child: &mut Child) {
+        let pgid = child.id();
+
+        if let Err(e) = self.kill_process_group(pgid, SIGTERM) {
+            warn!("Failed to send SIGTERM to process group {}: {}", pgid, e);
+        }
+
+        let grace_start = Instant::now();
+        while grace_start.elapsed() < TERMINATION_GRACE_PERIOD {
+            if matches!(child.try_wait(), Ok(Some(_))) {
+                return;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+
+        warn!("Process group {} ignored SIGTERM, sending SIGKILL", pgid);
+        if let Err(e) = self.kill_process_group(pgid, SIGKILL) {
+            warn!("Failed to send SIGKILL to process group {}: {}", pgid, e);
+        }
+        let _ = child.wait();
+    }
+
+    #[cfg(not(unix))]
+    fn terminate_then_kill(&self, child: &mut Child) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    /// Send `signal` to every process in the group headed by `pgid` (Unix
+    /// only), via `kill -{signal} -{pgid}` (a negative pid targets the whole
+    /// group). Used to reap a timed-out child's descendants without matching
+    /// unrelated processes by name.
+    #[cfg(unix)]
+    #[instrument(skip(self))]
+    pub fn kill_process_group(&self, pgid: u32, signal: i32) -> Result<()> {
+        debug!("Killing process group {} with signal {}", pgid, signal);
+
+        let result = self
+            .command("kill")
+            .arg(format!("-{signal}"))
+            .arg(format!("-{pgid}"))
+            .status();
+
+        match result {
+            Ok(()) => {
+                debug!("Process group {} killed successfully", pgid);
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to kill process group {}: {}", pgid, e);
+                Err(e)
+            }
+        }
+    }
 }
 
 impl Default for ProcessRunner {
@@ -257,6 +345,342 @@ impl Default for ProcessRunner {
     }
 }
 
+/// Fluent builder for a single child-process invocation.
+///
+/// Obtained via [`ProcessRunner::command`]; accumulates arguments, environment
+/// variables, and a working directory through chained setters, then executes
+/// with [`status`](Self::status) (inheriting stdio) or [`output`](Self::output)
+/// (capturing stdio into a [`ProcessResult`]).
+pub struct ProcessBuilder<'a> {
+    runner: &'a ProcessRunner,
+    program: String,
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    cwd: Option<PathBuf>,
+    inherit_stdio: bool,
+    elevated: bool,
+    timeout: Option<Duration>,
+}
+
+impl<'a> ProcessBuilder<'a> {
+    fn new(runner: &'a ProcessRunner, program: impl Into<String>) -> Self {
+        Self {
+            runner,
+            program: program.into(),
+            args: Vec::new(),
+            envs: Vec::new(),
+            cwd: None,
+            inherit_stdio: true,
+            elevated: false,
+            timeout: None,
+        }
+    }
+
+    /// Fail the command if it runs longer than `timeout`: the child is first
+    /// sent `SIGTERM`, given a grace period to exit, then `SIGKILL`'d.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run this command under a privilege-escalation tool (`sudo`, `run0`,
+    /// `pkexec`, or `doas`): the configured tool from
+    /// [`ProcessRunner::with_escalation`] if set, otherwise the first one
+    /// found in PATH. Falls back to running unprivileged (with a warning) if
+    /// none is available.
+    #[must_use]
+    pub fn elevated(mut self) -> Self {
+        self.elevated = true;
+        self
+    }
+
+    /// Append a single argument
+    #[must_use]
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Append multiple arguments
+    #[must_use]
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set a single environment variable for the child process
+    #[must_use]
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set multiple environment variables for the child process
+    #[must_use]
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.envs
+            .extend(vars.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Set the working directory the child process is spawned in
+    #[must_use]
+    pub fn cwd(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cwd = Some(dir.into());
+        self
+    }
+
+    /// Whether to inherit the parent's stdout/stderr when using [`status`](Self::status)
+    /// (ignored by [`output`](Self::output), which always captures). Defaults to `true`.
+    #[must_use]
+    pub const fn inherit_stdio(mut self, inherit: bool) -> Self {
+        self.inherit_stdio = inherit;
+        self
+    }
+
+    /// Resolve the program/args actually spawned, prefixing with the
+    /// escalation tool when `.elevated()` was requested.
+    fn effective_invocation(&self) -> (String, Vec<String>) {
+        if !self.elevated {
+            return (self.program.clone(), self.args.clone());
+        }
+
+        match self.runner.resolve_escalation_tool() {
+            Some(tool) => {
+                let mut args = vec![self.program.clone()];
+                args.extend(self.args.clone());
+                (tool, args)
+            }
+            None => {
+                warn!(
+                    "Elevation requested for `{}` but no escalation tool (sudo/run0/pkexec/doas) was found; running unprivileged",
+                    self.program
+                );
+                (self.program.clone(), self.args.clone())
+            }
+        }
+    }
+
+    /// Render the command and its arguments for logging/error reporting
+    fn cmd_str(&self) -> String {
+        let (program, args) = self.effective_invocation();
+        format!("{} {}", program, args.join(" "))
+    }
+
+    fn build_command(&self) -> Command {
+        let (program, args) = self.effective_invocation();
+        let mut cmd = Command::new(program);
+        cmd.args(&args);
+
+        // Put the child in its own process group (headed by itself) so a
+        // timeout can `kill_process_group` exactly its descendants instead
+        // of matching unrelated processes by program name.
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+
+        if let Some(dir) = &self.cwd {
+            cmd.current_dir(dir);
+        }
+
+        for (key, value) in &self.envs {
+            cmd.env(key, value);
+        }
+
+        cmd
+    }
+
+    /// Record this invocation into the runner's plan instead of executing it
+    fn record_planned(&self, plan: &Mutex<Vec<PlannedInvocation>>) {
+        let (program, args) = self.effective_invocation();
+        let invocation = PlannedInvocation {
+            program,
+            args,
+            env: self.envs.clone(),
+            cwd: self.cwd.clone(),
+        };
+        debug!("Recording planned command (dry run): {}", self.cmd_str());
+        plan.lock()
+            .expect("planned invocation list poisoned")
+            .push(invocation);
+    }
+
+    /// Run the command, returning `Ok(())` on success or a [`BuilderError::Process`]
+    /// describing the failure. Stdio is inherited or discarded per [`inherit_stdio`](Self::inherit_stdio).
+    ///
+    /// In dry-run mode the invocation is recorded instead of executed and this
+    /// always returns `Ok(())`.
+    #[instrument(skip(self), fields(program = %self.program))]
+    pub fn status(self) -> Result<()> {
+        if let Some(plan) = &self.runner.plan {
+            self.record_planned(plan);
+            return Ok(());
+        }
+
+        let cmd_str = self.cmd_str();
+
+        if self.runner.debug {
+            debug!("Running command: {}", cmd_str);
+            if !self.envs.is_empty() {
+                debug!("Environment variables: {:?}", self.envs);
+            }
+        } else {
+            info!("+ {}", cmd_str);
+        }
+
+        self.runner.emit(BuildEvent::CommandStarted {
+            command: cmd_str.clone(),
+        });
+        let start = Instant::now();
+
+        let mut cmd = self.build_command();
+        if self.inherit_stdio {
+            cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+        } else {
+            cmd.stdout(Stdio::null()).stderr(Stdio::null());
+        }
+
+        let child = cmd.spawn().map_err(|e| {
+            BuilderError::process(
+                cmd_str.clone(),
+                None,
+                String::new(),
+                format!("Failed to execute command: {e}"),
+            )
+        })?;
+
+        let result = self
+            .runner
+            .wait_for_child(child, &self.program, &cmd_str, self.timeout);
+
+        self.runner.emit(BuildEvent::CommandFinished {
+            command: cmd_str.clone(),
+            exit_code: result.as_ref().ok().and_then(std::process::ExitStatus::code),
+            stdout_len: 0,
+            stderr_len: 0,
+            duration_ms: start.elapsed().as_millis(),
+        });
+
+        let status = result?;
+
+        if !status.success() {
+            let exit_code = status.code();
+            return Err(BuilderError::process(
+                cmd_str,
+                exit_code,
+                String::new(),
+                format!("Command failed with exit code: {exit_code:?}"),
+            ));
+        }
+
+        debug!("Command completed successfully");
+        Ok(())
+    }
+
+    /// Run the command, capturing stdout/stderr into a [`ProcessResult`].
+    ///
+    /// In dry-run mode the invocation is recorded instead of executed and this
+    /// always returns a synthetic successful result with empty output.
+    #[instrument(skip(self), fields(program = %self.program))]
+    pub fn output(self) -> Result<ProcessResult> {
+        if let Some(plan) = &self.runner.plan {
+            self.record_planned(plan);
+            return Ok(ProcessResult {
+                exit_code: Some(0),
+                stdout: String::new(),
+                stderr: String::new(),
+                success: true,
+            });
+        }
+
+        let cmd_str = self.cmd_str();
+        debug!("Running command with output capture: {}", cmd_str);
+
+        self.runner.emit(BuildEvent::CommandStarted {
+            command: cmd_str.clone(),
+        });
+        let start = Instant::now();
+
+        let mut cmd = self.build_command();
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let mut child = cmd.spawn().map_err(|e| {
+            BuilderError::process(
+                cmd_str.clone(),
+                None,
+                String::new(),
+                format!("Failed to execute command: {e}"),
+            )
+        })?;
+
+        // Drain stdout/stderr on their own threads so a timeout kill isn't
+        // blocked by a full pipe buffer while we poll the child below.
+        let mut stdout_pipe = child.stdout.take().expect("stdout was piped");
+        let mut stderr_pipe = child.stderr.take().expect("stderr was piped");
+        let stdout_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stdout_pipe.read_to_end(&mut buf);
+            buf
+        });
+        let stderr_reader = std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = stderr_pipe.read_to_end(&mut buf);
+            buf
+        });
+
+        let result = self
+            .runner
+            .wait_for_child(child, &self.program, &cmd_str, self.timeout);
+
+        let stdout = String::from_utf8_lossy(&stdout_reader.join().unwrap_or_default()).to_string();
+        let stderr = String::from_utf8_lossy(&stderr_reader.join().unwrap_or_default()).to_string();
+
+        self.runner.emit(BuildEvent::CommandFinished {
+            command: cmd_str.clone(),
+            exit_code: result.as_ref().ok().and_then(std::process::ExitStatus::code),
+            stdout_len: stdout.len(),
+            stderr_len: stderr.len(),
+            duration_ms: start.elapsed().as_millis(),
+        });
+
+        let status = result?;
+        let success = status.success();
+        let exit_code = status.code();
+
+        debug!(
+            "Command finished: success={}, exit_code={:?}, stdout_len={}, stderr_len={}",
+            success,
+            exit_code,
+            stdout.len(),
+            stderr.len()
+        );
+
+        if !success {
+            debug!("Command stderr: {}", stderr);
+            return Err(BuilderError::process(cmd_str, exit_code, stdout, stderr));
+        }
+
+        Ok(ProcessResult {
+            exit_code,
+            stdout,
+            stderr,
+            success,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -273,7 +697,7 @@ mod tests {
     #[test]
     fn test_run_simple_command() {
         let runner = ProcessRunner::new(false);
-        let result = runner.run_command("echo", &["hello"]);
+        let result = runner.command("echo").arg("hello").status();
         assert!(result.is_ok());
     }
 
@@ -281,7 +705,9 @@ mod tests {
     fn test_run_command_with_output() {
         let runner = ProcessRunner::new(false);
         let result = runner
-            .run_command_with_output("echo", &["hello", "world"])
+            .command("echo")
+            .args(["hello", "world"])
+            .output()
             .unwrap();
 
         assert!(result.success);
@@ -304,7 +730,7 @@ mod tests {
     #[test]
     fn test_run_failing_command() {
         let runner = ProcessRunner::new(false);
-        let result = runner.run_command("false", &[]);
+        let result = runner.command("false").status();
         assert!(result.is_err());
 
         if let Err(BuilderError::Process {
@@ -321,10 +747,12 @@ mod tests {
     #[test]
     fn test_run_command_with_env() {
         let runner = ProcessRunner::new(false);
-        let env_vars = vec![("TEST_VAR".to_string(), "test_value".to_string())];
 
         let result = runner
-            .run_command_with_output_and_env("sh", &["-c", "echo $TEST_VAR"], &env_vars)
+            .command("sh")
+            .args(["-c", "echo $TEST_VAR"])
+            .env("TEST_VAR", "test_value")
+            .output()
             .unwrap();
 
         assert!(result.success);
@@ -332,27 +760,89 @@ mod tests {
     }
 
     #[test]
-    fn test_run_commands_sequence() {
+    fn test_command_with_cwd() {
         let runner = ProcessRunner::new(false);
-        let commands = vec![("echo", &["first"][..]), ("echo", &["second"][..])];
+        let temp_dir = std::env::temp_dir();
+
+        let result = runner.command("pwd").cwd(&temp_dir).output().unwrap();
+
+        assert!(result.success);
+        // Compare canonicalized paths since temp dirs may be symlinked (e.g. /tmp -> /private/tmp)
+        let printed = PathBuf::from(result.stdout.trim());
+        assert_eq!(
+            printed.canonicalize().unwrap(),
+            temp_dir.canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_elevated_prefixes_configured_escalation_tool() {
+        let runner = ProcessRunner::new(false).with_escalation("doas");
+        let (program, args) = runner.command("pacman").arg("-S").elevated().effective_invocation();
+
+        assert_eq!(program, "doas");
+        assert_eq!(args, vec!["pacman", "-S"]);
+    }
+
+    #[test]
+    fn test_not_elevated_leaves_invocation_unchanged() {
+        let runner = ProcessRunner::new(false).with_escalation("doas");
+        let (program, args) = runner.command("pacman").arg("-Qi").effective_invocation();
 
-        let results = runner.run_commands_sequence(&commands).unwrap();
-        assert_eq!(results.len(), 2);
-        assert_eq!(results[0].stdout.trim(), "first");
-        assert_eq!(results[1].stdout.trim(), "second");
+        assert_eq!(program, "pacman");
+        assert_eq!(args, vec!["-Qi"]);
     }
 
     #[test]
-    fn test_run_commands_sequence_failure() {
+    fn test_dry_run_records_instead_of_executing() {
+        let (runner, plan) = ProcessRunner::new_dry_run(false);
+
+        let result = runner
+            .command("rm")
+            .args(["-rf", "/should/not/run"])
+            .env("FOO", "bar")
+            .status();
+        assert!(result.is_ok());
+
+        let output = runner.command("echo").arg("hi").output().unwrap();
+        assert!(output.success);
+        assert!(output.stdout.is_empty());
+
+        let recorded = plan.lock().unwrap();
+        assert_eq!(recorded.len(), 2);
+        assert_eq!(recorded[0].program, "rm");
+        assert_eq!(recorded[0].args, vec!["-rf", "/should/not/run"]);
+        assert_eq!(recorded[0].env, vec![("FOO".to_string(), "bar".to_string())]);
+        assert_eq!(recorded[1].program, "echo");
+    }
+
+    #[test]
+    fn test_timeout_kills_long_running_command() {
         let runner = ProcessRunner::new(false);
-        let commands = vec![
-            ("echo", &["first"][..]),
-            ("false", &[][..]), // This will fail
-            ("echo", &["third"][..]),
-        ];
+        let result = runner
+            .command("sleep")
+            .arg("5")
+            .timeout(Duration::from_millis(200))
+            .status();
 
-        let result = runner.run_commands_sequence(&commands);
-        assert!(result.is_err());
+        match result {
+            Err(BuilderError::Timeout { timeout, .. }) => {
+                assert_eq!(timeout, Duration::from_millis(200));
+            }
+            other => panic!("Expected Timeout error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_timeout_does_not_trigger_for_fast_command() {
+        let runner = ProcessRunner::new(false);
+        let result = runner
+            .command("echo")
+            .arg("hi")
+            .timeout(Duration::from_secs(5))
+            .status();
+
+        assert!(result.is_ok());
     }
 
     #[cfg(unix)]
@@ -0,0 +1,81 @@
+//! String comparison utilities
+//!
+//! Provides fuzzy-matching helpers used for diagnostics (e.g. suggesting the
+//! intended key for a typo'd configuration option).
+
+/// Levenshtein edit distance between two strings: the minimum number of
+/// single-character insertions, deletions, or substitutions needed to turn
+/// `a` into `b`.
+#[must_use]
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (m, n) = (a.len(), b.len());
+
+    let mut d = vec![vec![0usize; n + 1]; m + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=m {
+        for j in 1..=n {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+        }
+    }
+
+    d[m][n]
+}
+
+/// Find the candidate closest to `key` by edit distance, if one is within
+/// `max_distance`. Used to power "did you mean" diagnostics.
+#[must_use]
+pub fn closest_match<'a>(key: &str, candidates: &[&'a str], max_distance: usize) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (*candidate, levenshtein(key, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein_identical() {
+        assert_eq!(levenshtein("config", "config"), 0);
+    }
+
+    #[test]
+    fn test_levenshtein_empty_strings() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("abc", ""), 3);
+    }
+
+    #[test]
+    fn test_levenshtein_typo() {
+        assert_eq!(levenshtein("primray", "primary"), 2);
+    }
+
+    #[test]
+    fn test_closest_match_within_threshold() {
+        let candidates = ["debug", "work_dir", "pkgbuild_path"];
+        assert_eq!(closest_match("wrok_dir", &candidates, 3), Some("work_dir"));
+    }
+
+    #[test]
+    fn test_closest_match_too_far() {
+        let candidates = ["debug", "work_dir"];
+        assert_eq!(
+            closest_match("completely_unrelated_key", &candidates, 3),
+            None
+        );
+    }
+}
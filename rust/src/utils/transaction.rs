@@ -0,0 +1,167 @@
+//! Transactional rollback of partially-produced build output
+//!
+//! A RAII guard that tracks paths an operation creates (or moves) and undoes
+//! them if the operation returns `Err` or panics, so a failed build or
+//! artifact collection never leaves half-finished output behind to poison
+//! the next run's glob-based detection. Only paths this run produced are
+//! ever tracked, so a failure never touches a user's pre-existing files.
+
+use std::path::PathBuf;
+use tracing::{debug, warn};
+
+/// A path this transaction is responsible for undoing on rollback.
+#[derive(Debug)]
+enum TrackedPath {
+    /// Newly created by this run; delete it entirely.
+    Remove(PathBuf),
+    /// An existing file this run moved from `original` to `current`; move it
+    /// back rather than deleting it, since `current` may be its only copy.
+    MoveBack { current: PathBuf, original: PathBuf },
+}
+
+/// Tracks paths created or moved during an operation and undoes them on
+/// drop, unless [`Transaction::commit`] was called first.
+#[derive(Debug, Default)]
+pub struct Transaction {
+    tracked: Vec<TrackedPath>,
+    committed: bool,
+}
+
+impl Transaction {
+    /// Start a new, empty transaction
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `path` was newly created by this run and should be
+    /// removed if the transaction is never committed. Never pass a path that
+    /// existed before the run.
+    pub fn track_created(&mut self, path: impl Into<PathBuf>) {
+        self.tracked.push(TrackedPath::Remove(path.into()));
+    }
+
+    /// Record that a file was moved from `original` to `current` by this
+    /// run; on rollback it is moved back rather than deleted, since
+    /// `original` no longer exists.
+    pub fn track_moved(&mut self, original: impl Into<PathBuf>, current: impl Into<PathBuf>) {
+        self.tracked.push(TrackedPath::MoveBack {
+            current: current.into(),
+            original: original.into(),
+        });
+    }
+
+    /// Mark the transaction successful: tracked paths are left as-is, and
+    /// `Drop` becomes a no-op.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+
+        for tracked in self.tracked.iter().rev() {
+            match tracked {
+                TrackedPath::Remove(path) => {
+                    let result = if path.is_dir() {
+                        std::fs::remove_dir_all(path)
+                    } else {
+                        std::fs::remove_file(path)
+                    };
+
+                    match result {
+                        Ok(()) => debug!("Rolled back: removed {}", path.display()),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => warn!("Failed to roll back {}: {}", path.display(), e),
+                    }
+                }
+                TrackedPath::MoveBack { current, original } => {
+                    match std::fs::rename(current, original) {
+                        Ok(()) => debug!(
+                            "Rolled back: restored {} to {}",
+                            current.display(),
+                            original.display()
+                        ),
+                        Err(e) => warn!(
+                            "Failed to restore {} to {}: {}",
+                            current.display(),
+                            original.display(),
+                            e
+                        ),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rolls_back_created_file_on_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("created.txt");
+        std::fs::write(&path, "data").unwrap();
+
+        {
+            let mut tx = Transaction::new();
+            tx.track_created(&path);
+        }
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_commit_keeps_created_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("created.txt");
+        std::fs::write(&path, "data").unwrap();
+
+        let mut tx = Transaction::new();
+        tx.track_created(&path);
+        tx.commit();
+
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_rolls_back_moved_file_by_restoring_original() {
+        let temp_dir = TempDir::new().unwrap();
+        let original = temp_dir.path().join("source.txt");
+        let current = temp_dir.path().join("dest.txt");
+        std::fs::write(&original, "data").unwrap();
+        std::fs::rename(&original, &current).unwrap();
+
+        {
+            let mut tx = Transaction::new();
+            tx.track_moved(&original, &current);
+        }
+
+        assert!(original.exists());
+        assert!(!current.exists());
+    }
+
+    #[test]
+    fn test_rolls_back_every_tracked_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.txt");
+        let second = temp_dir.path().join("second.txt");
+        std::fs::write(&first, "a").unwrap();
+        std::fs::write(&second, "b").unwrap();
+
+        let mut tx = Transaction::new();
+        tx.track_created(&first);
+        tx.track_created(&second);
+        drop(tx);
+
+        assert!(!first.exists());
+        assert!(!second.exists());
+    }
+}